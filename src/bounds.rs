@@ -0,0 +1,133 @@
+use crate::{intersection::EPSILON, matrix::Matrix4x4, ray::Ray, tuple::Tuple};
+
+/// An axis-aligned bounding box. The default box is empty and grows as
+/// points and other boxes are added to it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundingBox {
+    pub min: Tuple,
+    pub max: Tuple,
+}
+
+impl BoundingBox {
+    pub fn new(min: Tuple, max: Tuple) -> Self {
+        Self { min, max }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z
+    }
+    pub fn add_point(&mut self, point: Tuple) {
+        self.min = Tuple::point(
+            self.min.x.min(point.x),
+            self.min.y.min(point.y),
+            self.min.z.min(point.z),
+        );
+        self.max = Tuple::point(
+            self.max.x.max(point.x),
+            self.max.y.max(point.y),
+            self.max.z.max(point.z),
+        );
+    }
+    pub fn merge(&mut self, other: &BoundingBox) {
+        if !other.is_empty() {
+            self.add_point(other.min);
+            self.add_point(other.max);
+        }
+    }
+    pub fn contains_point(&self, point: Tuple) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x)
+            && (self.min.y..=self.max.y).contains(&point.y)
+            && (self.min.z..=self.max.z).contains(&point.z)
+    }
+    pub fn contains_box(&self, other: &BoundingBox) -> bool {
+        self.contains_point(other.min) && self.contains_point(other.max)
+    }
+    /// Infinite for unbounded boxes and zero for empty ones.
+    pub fn surface_area(&self) -> f64 {
+        if self.is_empty() {
+            return 0.;
+        }
+        let d = self.max - self.min;
+        if !(d.x.is_finite() && d.y.is_finite() && d.z.is_finite()) {
+            return f64::INFINITY;
+        }
+        2. * (d.x * d.y + d.y * d.z + d.z * d.x)
+    }
+    /// The box enclosing this one after transforming it by `m`.
+    pub fn transform(&self, m: Matrix4x4) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
+        // Rather than transforming all eight corners, each output extent is
+        // the translation plus the smaller (or larger) of the two scaled
+        // input extents on every axis. Skipping zero entries keeps infinite
+        // boxes, like a plane's, from turning into NaN.
+        let from = [
+            (self.min.x, self.max.x),
+            (self.min.y, self.max.y),
+            (self.min.z, self.max.z),
+        ];
+        let mut to = [(0., 0.); 3];
+        for (i, extent) in to.iter_mut().enumerate() {
+            *extent = (m[i][3], m[i][3]);
+            for (j, (lo, hi)) in from.iter().enumerate() {
+                if m[i][j] == 0. {
+                    continue;
+                }
+                let (a, b) = (m[i][j] * lo, m[i][j] * hi);
+                extent.0 += a.min(b);
+                extent.1 += a.max(b);
+            }
+        }
+        Self::new(
+            Tuple::point(to[0].0, to[1].0, to[2].0),
+            Tuple::point(to[0].1, to[1].1, to[2].1),
+        )
+    }
+    /// Where `ray` enters and leaves the box, if it hits it at all.
+    pub fn intersect_range(&self, ray: &Ray) -> Option<(f64, f64)> {
+        let (xtmin, xtmax) = check_axis(ray.origin.x, ray.direction.x, self.min.x, self.max.x);
+        let (ytmin, ytmax) = check_axis(ray.origin.y, ray.direction.y, self.min.y, self.max.y);
+        let (ztmin, ztmax) = check_axis(ray.origin.z, ray.direction.z, self.min.z, self.max.z);
+
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+        if tmin > tmax {
+            None
+        } else {
+            Some((tmin, tmax))
+        }
+    }
+    pub fn intersects(&self, ray: &Ray) -> bool {
+        self.intersect_range(ray).is_some()
+    }
+}
+impl Default for BoundingBox {
+    fn default() -> Self {
+        Self::new(
+            Tuple::point(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+            Tuple::point(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+        )
+    }
+}
+
+/// Where a ray enters and leaves the slab between `min` and `max` on one
+/// axis.
+pub fn check_axis(origin: f64, direction: f64, min: f64, max: f64) -> (f64, f64) {
+    let tmin_numerator = min - origin;
+    let tmax_numerator = max - origin;
+
+    let (tmin, tmax) = if direction.abs() >= EPSILON {
+        (tmin_numerator / direction, tmax_numerator / direction)
+    } else {
+        (
+            tmin_numerator * f64::INFINITY,
+            tmax_numerator * f64::INFINITY,
+        )
+    };
+
+    if tmin > tmax {
+        (tmax, tmin)
+    } else {
+        (tmin, tmax)
+    }
+}
@@ -0,0 +1,304 @@
+use crate::{
+    bounds::BoundingBox,
+    intersection::Intersections,
+    matrix::Matrix4x4,
+    ray::Ray,
+    shapes::{Object, Shape},
+    tuple::Tuple,
+};
+
+/// Nodes with this many objects or fewer only split if it is cheaper.
+const MAX_LEAF_SIZE: usize = 4;
+/// How many buckets candidate split planes are binned into on each axis.
+const BINS: usize = 12;
+/// The cost of visiting a node, relative to intersecting one object.
+const TRAVERSAL_COST: f64 = 1.;
+
+#[derive(Clone, Copy, Debug)]
+enum NodeKind {
+    /// `count` objects starting at `first`.
+    Leaf { first: usize, count: usize },
+    /// The left child directly follows its parent; `right` is the index of
+    /// the right child.
+    Interior { right: usize },
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    bounds: BoundingBox,
+    kind: NodeKind,
+}
+
+/// An object waiting to be placed in the hierarchy.
+struct Item {
+    bounds: BoundingBox,
+    centroid: Tuple,
+    object: Object,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BvhStats {
+    pub node_count: usize,
+    pub leaf_count: usize,
+    pub depth: usize,
+    pub average_leaf_size: f64,
+}
+
+/// A bounding volume hierarchy over a flat list of objects, split with the
+/// surface area heuristic and stored depth-first in a single array. Like a
+/// group, each object's transform is relative to the BVH's object space.
+#[derive(Debug)]
+pub struct Bvh {
+    objects: Vec<Object>,
+    nodes: Vec<Node>,
+}
+
+impl Bvh {
+    pub fn new(objects: Vec<Object>) -> Self {
+        let mut items: Vec<Item> = objects
+            .into_iter()
+            .map(|object| {
+                let bounds = object.parent_space_bounds();
+                Item {
+                    bounds,
+                    centroid: centroid(&bounds),
+                    object,
+                }
+            })
+            .collect();
+        let mut nodes = Vec::new();
+        if !items.is_empty() {
+            build(&mut items, 0, &mut nodes);
+        }
+        Self {
+            objects: items.into_iter().map(|item| item.object).collect(),
+            nodes,
+        }
+    }
+    /// The objects, in the order the leaves refer to them.
+    pub fn objects(&self) -> &[Object] {
+        &self.objects
+    }
+    pub fn stats(&self) -> BvhStats {
+        let mut stats = BvhStats {
+            node_count: self.nodes.len(),
+            leaf_count: 0,
+            depth: 0,
+            average_leaf_size: 0.,
+        };
+        if self.nodes.is_empty() {
+            return stats;
+        }
+        let mut stack = vec![(0, 1)];
+        while let Some((index, depth)) = stack.pop() {
+            stats.depth = stats.depth.max(depth);
+            match self.nodes[index].kind {
+                NodeKind::Leaf { .. } => stats.leaf_count += 1,
+                NodeKind::Interior { right } => {
+                    stack.push((index + 1, depth + 1));
+                    stack.push((right, depth + 1));
+                }
+            }
+        }
+        if stats.leaf_count > 0 {
+            stats.average_leaf_size = self.objects.len() as f64 / stats.leaf_count as f64;
+        }
+        stats
+    }
+    /// Calls `visit` with the objects of every leaf whose box `ray` passes
+    /// through between `min_t` and `max_t`, nearest box first. Stops as soon
+    /// as `visit` returns true, and returns whether it did.
+    fn traverse<'a>(
+        &'a self,
+        ray: &Ray,
+        min_t: f64,
+        max_t: f64,
+        mut visit: impl FnMut(&'a [Object]) -> bool,
+    ) -> bool {
+        let entry = |index: usize| {
+            self.nodes[index]
+                .bounds
+                .intersect_range(ray)
+                .filter(|&(tmin, tmax)| tmin <= max_t && tmax >= min_t)
+                .map(|(tmin, _)| tmin)
+        };
+        if self.nodes.is_empty() || entry(0).is_none() {
+            return false;
+        }
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            match self.nodes[index].kind {
+                NodeKind::Leaf { first, count } => {
+                    if visit(&self.objects[first..first + count]) {
+                        return true;
+                    }
+                }
+                NodeKind::Interior { right } => {
+                    // Push the farther child first so the nearer one is
+                    // visited next.
+                    match (entry(index + 1), entry(right)) {
+                        (Some(l), Some(r)) if l <= r => stack.extend([right, index + 1]),
+                        (Some(_), Some(_)) => stack.extend([index + 1, right]),
+                        (Some(_), None) => stack.push(index + 1),
+                        (None, Some(_)) => stack.push(right),
+                        (None, None) => {}
+                    }
+                }
+            }
+        }
+        false
+    }
+}
+
+/// The middle of a box, falling back to a finite face (or the origin) on
+/// unbounded axes so that planes can still be binned.
+fn centroid(bounds: &BoundingBox) -> Tuple {
+    let mid = |lo: f64, hi: f64| match (lo.is_finite(), hi.is_finite()) {
+        (true, true) => (lo + hi) / 2.,
+        (true, false) => lo,
+        (false, true) => hi,
+        (false, false) => 0.,
+    };
+    Tuple::point(
+        mid(bounds.min.x, bounds.max.x),
+        mid(bounds.min.y, bounds.max.y),
+        mid(bounds.min.z, bounds.max.z),
+    )
+}
+
+fn component(t: Tuple, axis: usize) -> f64 {
+    match axis {
+        0 => t.x,
+        1 => t.y,
+        _ => t.z,
+    }
+}
+
+/// Appends the subtree for `items` to `nodes`. `first` is the position of
+/// `items[0]` in the final object list.
+fn build(items: &mut [Item], first: usize, nodes: &mut Vec<Node>) {
+    let mut bounds = BoundingBox::default();
+    let mut centroids = BoundingBox::default();
+    for item in items.iter() {
+        bounds.merge(&item.bounds);
+        centroids.add_point(item.centroid);
+    }
+    let index = nodes.len();
+    nodes.push(Node {
+        bounds,
+        kind: NodeKind::Leaf {
+            first,
+            count: items.len(),
+        },
+    });
+
+    let Some((cost, axis, split)) = best_split(items, &centroids) else {
+        return;
+    };
+    let area = bounds.surface_area();
+    if items.len() <= MAX_LEAF_SIZE && TRAVERSAL_COST * area + cost >= items.len() as f64 * area {
+        return;
+    }
+
+    // Partition in place so each child owns a contiguous run of objects.
+    let mut mid = 0;
+    for i in 0..items.len() {
+        if bin(&items[i], &centroids, axis) < split {
+            items.swap(i, mid);
+            mid += 1;
+        }
+    }
+    let (left, right) = items.split_at_mut(mid);
+    build(left, first, nodes);
+    let right_index = nodes.len();
+    build(right, first + mid, nodes);
+    nodes[index].kind = NodeKind::Interior { right: right_index };
+}
+
+fn bin(item: &Item, centroids: &BoundingBox, axis: usize) -> usize {
+    let min = component(centroids.min, axis);
+    let extent = component(centroids.max, axis) - min;
+    let b = ((component(item.centroid, axis) - min) / extent * BINS as f64) as usize;
+    b.min(BINS - 1)
+}
+
+/// The cheapest way to split `items` by binning their centroids, as
+/// `(cost, axis, first bin on the right)`, where the cost is the sum of each
+/// side's object count times its surface area. `None` when every centroid
+/// falls in the same place.
+fn best_split(items: &[Item], centroids: &BoundingBox) -> Option<(f64, usize, usize)> {
+    let mut best: Option<(f64, usize, usize)> = None;
+    for axis in 0..3 {
+        let extent = component(centroids.max, axis) - component(centroids.min, axis);
+        if extent <= 0. {
+            continue;
+        }
+        let mut counts = [0; BINS];
+        let mut boxes = [BoundingBox::default(); BINS];
+        for item in items {
+            let b = bin(item, centroids, axis);
+            counts[b] += 1;
+            boxes[b].merge(&item.bounds);
+        }
+        // Sweep from the right so each split's right-hand area is known.
+        let mut right_areas = [0.; BINS];
+        let mut right_box = BoundingBox::default();
+        for b in (1..BINS).rev() {
+            right_box.merge(&boxes[b]);
+            right_areas[b] = right_box.surface_area();
+        }
+        let (mut left_box, mut left_count) = (BoundingBox::default(), 0);
+        for split in 1..BINS {
+            left_box.merge(&boxes[split - 1]);
+            left_count += counts[split - 1];
+            let right_count = items.len() - left_count;
+            if left_count == 0 || right_count == 0 {
+                continue;
+            }
+            let cost = left_count as f64 * left_box.surface_area()
+                + right_count as f64 * right_areas[split];
+            if best.is_none_or(|(best_cost, _, _)| cost < best_cost) {
+                best = Some((cost, axis, split));
+            }
+        }
+    }
+    best
+}
+
+impl Shape for Bvh {
+    fn local_intersect<'a>(&'a self, ray: &Ray, _object: &'a Object) -> Intersections<'a> {
+        let mut xs = Intersections::default();
+        self.traverse(ray, f64::NEG_INFINITY, f64::INFINITY, |objects| {
+            for object in objects {
+                xs.merge(object.intersect(*ray));
+            }
+            false
+        });
+        xs
+    }
+    fn intersects_before(&self, ray: &Ray, _object: &Object, distance: f64) -> bool {
+        self.traverse(ray, 0., distance, |objects| {
+            objects
+                .iter()
+                .any(|object| object.intersects_before(*ray, distance))
+        })
+    }
+    /// Panics: the hierarchy only partitions its objects, and every hit it
+    /// reports is on one of them.
+    fn local_normal_at(&self, _point: Tuple) -> Tuple {
+        panic!("bounding volume hierarchies have no surface normal of their own")
+    }
+    fn bounds(&self) -> BoundingBox {
+        self.nodes
+            .first()
+            .map_or_else(BoundingBox::default, |node| node.bounds)
+    }
+    fn set_world_transform(&mut self, world_to_object: Matrix4x4) {
+        for object in &mut self.objects {
+            object.set_parent_transform(world_to_object);
+        }
+    }
+    fn includes(&self, object: &Object) -> bool {
+        self.objects.iter().any(|child| child.includes(object))
+    }
+}
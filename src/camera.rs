@@ -0,0 +1,73 @@
+use crate::{canvas::Canvas, matrix::Matrix4x4, ray::Ray, tuple::Tuple, world::World};
+
+pub struct Camera {
+    hsize: usize,
+    vsize: usize,
+    field_of_view: f64,
+    transform: Matrix4x4,
+    inverse: Matrix4x4,
+    half_width: f64,
+    half_height: f64,
+    pixel_size: f64,
+}
+
+impl Camera {
+    /// `transform` maps world space into camera space, typically built with
+    /// `Matrix4x4::view_transform`.
+    pub fn new(hsize: usize, vsize: usize, field_of_view: f64, transform: Matrix4x4) -> Self {
+        let half_view = (field_of_view / 2.).tan();
+        let aspect = hsize as f64 / vsize as f64;
+        let (half_width, half_height) = if aspect >= 1. {
+            (half_view, half_view / aspect)
+        } else {
+            (half_view * aspect, half_view)
+        };
+        Self {
+            hsize,
+            vsize,
+            field_of_view,
+            transform,
+            inverse: transform.inverse(),
+            half_width,
+            half_height,
+            pixel_size: half_width * 2. / hsize as f64,
+        }
+    }
+    pub fn get_hsize(&self) -> usize {
+        self.hsize
+    }
+    pub fn get_vsize(&self) -> usize {
+        self.vsize
+    }
+    pub fn get_field_of_view(&self) -> f64 {
+        self.field_of_view
+    }
+    pub fn get_transform(&self) -> Matrix4x4 {
+        self.transform
+    }
+    pub fn get_pixel_size(&self) -> f64 {
+        self.pixel_size
+    }
+    /// A ray from the camera through the center of pixel (`x`, `y`).
+    pub fn ray_for_pixel(&self, x: usize, y: usize) -> Ray {
+        let xoffset = (x as f64 + 0.5) * self.pixel_size;
+        let yoffset = (y as f64 + 0.5) * self.pixel_size;
+        // The camera looks toward -z, so +x is to the left.
+        let world_x = self.half_width - xoffset;
+        let world_y = self.half_height - yoffset;
+
+        let pixel = self.inverse * Tuple::point(world_x, world_y, -1.);
+        let origin = self.inverse * Tuple::point(0., 0., 0.);
+        Ray::new(origin, (pixel - origin).normalize())
+    }
+    pub fn render(&self, world: &World) -> Canvas {
+        let mut image = Canvas::new(self.hsize, self.vsize);
+        for y in 0..self.vsize {
+            for x in 0..self.hsize {
+                let ray = self.ray_for_pixel(x, y);
+                image.set_pixel(x, y, world.color_at(&ray, world.max_depth));
+            }
+        }
+        image
+    }
+}
@@ -1,7 +1,98 @@
-use std::{fs::File, io::Write};
+use std::{
+    fmt::Display,
+    fs::{self, File},
+    io::{self, Write},
+};
 
 use crate::color::Color;
 
+#[derive(Debug)]
+pub enum PpmError {
+    Io(io::Error),
+    /// A missing or malformed magic number, size or maximum value.
+    Header(String),
+    /// A sample that isn't a number or is above the maximum value.
+    Pixel {
+        x: usize,
+        y: usize,
+        message: String,
+    },
+    /// The data ran out after `found` of the `expected` samples.
+    Truncated {
+        expected: usize,
+        found: usize,
+    },
+}
+impl Display for PpmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PpmError::Io(e) => write!(f, "failed to read PPM file: {}", e),
+            PpmError::Header(message) => write!(f, "invalid PPM header: {}", message),
+            PpmError::Pixel { x, y, message } => write!(f, "pixel ({}, {}): {}", x, y, message),
+            PpmError::Truncated { expected, found } => {
+                write!(f, "PPM data ends after {} of {} samples", found, expected)
+            }
+        }
+    }
+}
+impl std::error::Error for PpmError {}
+impl From<io::Error> for PpmError {
+    fn from(e: io::Error) -> Self {
+        PpmError::Io(e)
+    }
+}
+
+/// Walks the whitespace-separated tokens of a PPM file, skipping comments.
+struct PpmReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> PpmReader<'a> {
+    fn skip_whitespace_and_comments(&mut self) {
+        while let Some(&byte) = self.data.get(self.position) {
+            if byte == b'#' {
+                while self.data.get(self.position).is_some_and(|&b| b != b'\n') {
+                    self.position += 1;
+                }
+            } else if byte.is_ascii_whitespace() {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+    }
+    fn token(&mut self) -> Option<&'a [u8]> {
+        self.skip_whitespace_and_comments();
+        let start = self.position;
+        while self
+            .data
+            .get(self.position)
+            .is_some_and(|&b| !b.is_ascii_whitespace() && b != b'#')
+        {
+            self.position += 1;
+        }
+        (self.position > start).then(|| &self.data[start..self.position])
+    }
+    fn header_number(&mut self, name: &str) -> Result<usize, PpmError> {
+        let token = self
+            .token()
+            .ok_or_else(|| PpmError::Header(format!("missing {}", name)))?;
+        parse_number(token).ok_or_else(|| {
+            PpmError::Header(format!(
+                "invalid {} {:?}",
+                name,
+                String::from_utf8_lossy(token)
+            ))
+        })
+    }
+}
+
+fn parse_number(token: &[u8]) -> Option<usize> {
+    std::str::from_utf8(token).ok()?.parse().ok()
+}
+
+#[derive(Clone, Debug)]
 pub struct Canvas {
     width: usize,
     height: usize,
@@ -28,16 +119,16 @@ impl Canvas {
         self.pixels[y * self.width + x]
     }
     fn ppm_header(&self, file: &mut File) {
-        file.write(format!("P3\n{} {}\n255\n", self.width, self.height).as_bytes())
+        file.write_all(format!("P3\n{} {}\n255\n", self.width, self.height).as_bytes())
             .expect("Failed to write header to file.");
     }
     fn ppm_body(&self, file: &mut File) {
         let mut i = 0;
         for pixel in &self.pixels {
-            file.write(format!("{} ", pixel).as_bytes())
+            file.write_all(format!("{} ", pixel).as_bytes())
                 .expect("Failed to write pixel to file.");
             if i >= 5 {
-                file.write("\n".as_bytes())
+                file.write_all("\n".as_bytes())
                     .expect("Failed to write new-line character to file.");
                 i = 0;
             } else {
@@ -45,9 +136,115 @@ impl Canvas {
             }
         }
     }
-    pub fn write_ppm(&self, filename: &'static str) {
+    pub fn write_ppm(&self, filename: &str) {
         let mut file = File::create(filename).expect("Failed to create file.");
         self.ppm_header(&mut file);
         self.ppm_body(&mut file);
     }
+    /// Reads a plain (P3) or raw (P6) PPM image.
+    pub fn read_ppm(filename: &str) -> Result<Self, PpmError> {
+        Self::parse_ppm(&fs::read(filename)?)
+    }
+    /// Parses the contents of a plain (P3) or raw (P6) PPM image. Samples
+    /// are scaled by the image's maximum value into 0..1.
+    pub fn parse_ppm(data: &[u8]) -> Result<Self, PpmError> {
+        let mut reader = PpmReader { data, position: 0 };
+        let binary = match reader.token() {
+            Some(b"P3") => false,
+            Some(b"P6") => true,
+            Some(magic) => {
+                return Err(PpmError::Header(format!(
+                    "unsupported magic number {:?}",
+                    String::from_utf8_lossy(magic)
+                )))
+            }
+            None => return Err(PpmError::Header("missing magic number".to_string())),
+        };
+        let width = reader.header_number("width")?;
+        let height = reader.header_number("height")?;
+        let max = reader.header_number("maximum value")?;
+        if width == 0 || height == 0 {
+            return Err(PpmError::Header(format!(
+                "image is {} by {} pixels",
+                width, height
+            )));
+        }
+        if !(1..=65535).contains(&max) {
+            return Err(PpmError::Header(format!(
+                "maximum value {} is outside 1..=65535",
+                max
+            )));
+        }
+
+        let expected = width
+            .checked_mul(height)
+            .and_then(|pixels| pixels.checked_mul(3))
+            .ok_or_else(|| {
+                PpmError::Header(format!("image size {} by {} is too large", width, height))
+            })?;
+        // The header can claim more samples than the data holds, so don't
+        // reserve room for them up front.
+        let mut samples = Vec::with_capacity(expected.min(data.len()));
+        if binary {
+            // Raw data starts after exactly one whitespace byte, with two
+            // big-endian bytes per sample when they don't fit in one.
+            match data.get(reader.position) {
+                Some(byte) if byte.is_ascii_whitespace() => reader.position += 1,
+                _ => {
+                    return Err(PpmError::Header(
+                        "expected whitespace after maximum value".to_string(),
+                    ))
+                }
+            }
+            let bytes = &data[reader.position..];
+            if max < 256 {
+                samples.extend(bytes.iter().take(expected).map(|&b| b as usize));
+            } else {
+                samples.extend(
+                    bytes
+                        .chunks_exact(2)
+                        .take(expected)
+                        .map(|pair| (pair[0] as usize) << 8 | pair[1] as usize),
+                );
+            }
+        } else {
+            while samples.len() < expected {
+                let Some(token) = reader.token() else {
+                    break;
+                };
+                let pixel = samples.len() / 3;
+                let sample = parse_number(token).ok_or_else(|| PpmError::Pixel {
+                    x: pixel % width,
+                    y: pixel / width,
+                    message: format!("invalid sample {:?}", String::from_utf8_lossy(token)),
+                })?;
+                samples.push(sample);
+            }
+        }
+        if samples.len() < expected {
+            return Err(PpmError::Truncated {
+                expected,
+                found: samples.len(),
+            });
+        }
+
+        let mut canvas = Canvas::new(width, height);
+        for (pixel, rgb) in samples.chunks_exact(3).enumerate() {
+            let (x, y) = (pixel % width, pixel / width);
+            if let Some(sample) = rgb.iter().find(|&&sample| sample > max) {
+                return Err(PpmError::Pixel {
+                    x,
+                    y,
+                    message: format!("sample {} is above the maximum value {}", sample, max),
+                });
+            }
+            let scale = |sample: usize| sample as f64 / max as f64;
+            canvas.set_pixel(
+                x,
+                y,
+                Color::new(scale(rgb[0]), scale(rgb[1]), scale(rgb[2])),
+            );
+        }
+        Ok(canvas)
+    }
 }
@@ -10,9 +10,18 @@ pub struct Color {
 }
 
 impl Color {
-    pub fn new(r: f64, g: f64, b: f64) -> Self {
+    pub const fn new(r: f64, g: f64, b: f64) -> Self {
         Self { r, g, b }
     }
+    pub fn get_red(&self) -> f64 {
+        self.r
+    }
+    pub fn get_green(&self) -> f64 {
+        self.g
+    }
+    pub fn get_blue(&self) -> f64 {
+        self.b
+    }
 }
 impl Add for Color {
     type Output = Self;
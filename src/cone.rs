@@ -0,0 +1,107 @@
+use crate::{
+    bounds::BoundingBox,
+    intersection::{Intersection, Intersections, EPSILON},
+    ray::Ray,
+    shapes::{Object, Shape},
+    tuple::Tuple,
+};
+
+/// The double-napped cone x² + z² = y² in object space, truncated to
+/// `minimum..maximum` (both exclusive) and capped at both ends when `closed`.
+#[derive(Debug)]
+pub struct Cone {
+    pub minimum: f64,
+    pub maximum: f64,
+    pub closed: bool,
+}
+
+impl Cone {
+    pub fn new(minimum: f64, maximum: f64, closed: bool) -> Self {
+        Self {
+            minimum,
+            maximum,
+            closed,
+        }
+    }
+    fn intersect_caps<'a>(&self, ray: &Ray, object: &'a Object, xs: &mut Vec<Intersection<'a>>) {
+        if !self.closed || ray.direction.y.abs() < EPSILON {
+            return;
+        }
+        // The radius of each cap is the distance of its plane from the apex.
+        for y in [self.minimum, self.maximum] {
+            let t = (y - ray.origin.y) / ray.direction.y;
+            if check_cap(ray, t, y.abs()) {
+                xs.push(Intersection::new(t, object));
+            }
+        }
+    }
+}
+impl Default for Cone {
+    /// An infinitely long, open cone.
+    fn default() -> Self {
+        Self::new(f64::NEG_INFINITY, f64::INFINITY, false)
+    }
+}
+
+/// Whether the ray at `t` is within `radius` of the y axis.
+fn check_cap(ray: &Ray, t: f64, radius: f64) -> bool {
+    let x = ray.origin.x + t * ray.direction.x;
+    let z = ray.origin.z + t * ray.direction.z;
+    (x * x + z * z) <= radius * radius
+}
+
+impl Shape for Cone {
+    fn local_intersect<'a>(&'a self, ray: &Ray, object: &'a Object) -> Intersections<'a> {
+        let (o, d) = (ray.origin, ray.direction);
+        let a = d.x * d.x - d.y * d.y + d.z * d.z;
+        let b = 2. * o.x * d.x - 2. * o.y * d.y + 2. * o.z * d.z;
+        let c = o.x * o.x - o.y * o.y + o.z * o.z;
+
+        let mut ts = Vec::new();
+        if a.abs() < EPSILON {
+            // The ray is parallel to one of the nappes, so it crosses the
+            // other one exactly once, unless it misses both entirely.
+            if b.abs() >= EPSILON {
+                ts.push(-c / (2. * b));
+            }
+        } else {
+            let disc = b * b - 4. * a * c;
+            if disc < 0. {
+                return Intersections::default();
+            }
+            ts.push((-b - disc.sqrt()) / (2. * a));
+            ts.push((-b + disc.sqrt()) / (2. * a));
+        }
+
+        let mut xs = Vec::new();
+        for t in ts {
+            let y = o.y + t * d.y;
+            if self.minimum < y && y < self.maximum {
+                xs.push(Intersection::new(t, object));
+            }
+        }
+
+        self.intersect_caps(ray, object, &mut xs);
+        Intersections::new(xs)
+    }
+    fn local_normal_at(&self, point: Tuple) -> Tuple {
+        let dist = point.x * point.x + point.z * point.z;
+        if dist < self.maximum * self.maximum && point.y >= self.maximum - EPSILON {
+            Tuple::vector(0., 1., 0.)
+        } else if dist < self.minimum * self.minimum && point.y <= self.minimum + EPSILON {
+            Tuple::vector(0., -1., 0.)
+        } else {
+            let y = dist.sqrt();
+            let y = if point.y > 0. { -y } else { y };
+            Tuple::vector(point.x, y, point.z)
+        }
+    }
+    fn bounds(&self) -> BoundingBox {
+        // The radius at any height is the distance from the apex.
+        let limit = self.minimum.abs().max(self.maximum.abs());
+        BoundingBox::new(
+            Tuple::point(-limit, self.minimum, -limit),
+            Tuple::point(limit, self.maximum, limit),
+        )
+    }
+}
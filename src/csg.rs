@@ -0,0 +1,99 @@
+use crate::{
+    bounds::BoundingBox,
+    intersection::Intersections,
+    matrix::Matrix4x4,
+    ray::Ray,
+    shapes::{Object, Shape},
+    tuple::Tuple,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+/// Whether a hit on one operand survives `operation`. `lhit` says the hit
+/// is on the left operand, and `inl`/`inr` whether it lies inside the left
+/// and right operands.
+pub fn intersection_allowed(operation: Operation, lhit: bool, inl: bool, inr: bool) -> bool {
+    match operation {
+        Operation::Union => (lhit && !inr) || (!lhit && !inl),
+        Operation::Intersection => (lhit && inr) || (!lhit && inl),
+        Operation::Difference => (lhit && !inr) || (!lhit && inl),
+    }
+}
+
+/// Constructive solid geometry: two objects combined by an `Operation`.
+/// Both operands are transformed relative to the CSG's object space.
+#[derive(Debug)]
+pub struct Csg {
+    operation: Operation,
+    left: Object,
+    right: Object,
+}
+
+impl Csg {
+    pub fn new(operation: Operation, left: Object, right: Object) -> Self {
+        Self {
+            operation,
+            left,
+            right,
+        }
+    }
+    pub fn operation(&self) -> Operation {
+        self.operation
+    }
+    pub fn left(&self) -> &Object {
+        &self.left
+    }
+    pub fn right(&self) -> &Object {
+        &self.right
+    }
+    /// Keeps the intersections that lie on the surface of the combined
+    /// shape, walking them in order while tracking whether the ray is
+    /// currently inside each operand.
+    pub fn filter_intersections<'a>(&self, xs: &Intersections<'a>) -> Intersections<'a> {
+        let mut inl = false;
+        let mut inr = false;
+        let mut result = Vec::new();
+        for i in xs.iter() {
+            let lhit = self.left.includes(i.object());
+            if intersection_allowed(self.operation, lhit, inl, inr) {
+                result.push(*i);
+            }
+            if lhit {
+                inl = !inl;
+            } else {
+                inr = !inr;
+            }
+        }
+        Intersections::new(result)
+    }
+}
+
+impl Shape for Csg {
+    fn local_intersect<'a>(&'a self, ray: &Ray, _object: &'a Object) -> Intersections<'a> {
+        let mut xs = self.left.intersect(*ray);
+        xs.merge(self.right.intersect(*ray));
+        self.filter_intersections(&xs)
+    }
+    /// The filtered hits belong to the operands' leaves, which supply the
+    /// normals; asking the CSG itself panics.
+    fn local_normal_at(&self, _point: Tuple) -> Tuple {
+        panic!("CSG shapes have no surface normal of their own")
+    }
+    fn bounds(&self) -> BoundingBox {
+        let mut bounds = self.left.parent_space_bounds();
+        bounds.merge(&self.right.parent_space_bounds());
+        bounds
+    }
+    fn set_world_transform(&mut self, world_to_object: Matrix4x4) {
+        self.left.set_parent_transform(world_to_object);
+        self.right.set_parent_transform(world_to_object);
+    }
+    fn includes(&self, object: &Object) -> bool {
+        self.left.includes(object) || self.right.includes(object)
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{
+    bounds::{check_axis, BoundingBox},
+    intersection::{Intersection, Intersections},
+    ray::Ray,
+    shapes::{Object, Shape},
+    tuple::Tuple,
+};
+
+/// The axis-aligned cube spanning -1..1 on every axis in object space.
+#[derive(Debug)]
+pub struct Cube;
+
+impl Shape for Cube {
+    fn local_intersect<'a>(&'a self, ray: &Ray, object: &'a Object) -> Intersections<'a> {
+        let (xtmin, xtmax) = check_axis(ray.origin.x, ray.direction.x, -1., 1.);
+        let (ytmin, ytmax) = check_axis(ray.origin.y, ray.direction.y, -1., 1.);
+        let (ztmin, ztmax) = check_axis(ray.origin.z, ray.direction.z, -1., 1.);
+
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+
+        if tmin > tmax {
+            Intersections::default()
+        } else {
+            Intersections::new(vec![
+                Intersection::new(tmin, object),
+                Intersection::new(tmax, object),
+            ])
+        }
+    }
+    fn local_normal_at(&self, point: Tuple) -> Tuple {
+        // The face a point lies on is the one its largest component points at.
+        let maxc = point.x.abs().max(point.y.abs()).max(point.z.abs());
+        if maxc == point.x.abs() {
+            Tuple::vector(point.x, 0., 0.)
+        } else if maxc == point.y.abs() {
+            Tuple::vector(0., point.y, 0.)
+        } else {
+            Tuple::vector(0., 0., point.z)
+        }
+    }
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(Tuple::point(-1., -1., -1.), Tuple::point(1., 1., 1.))
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{
+    bounds::BoundingBox,
+    intersection::{Intersection, Intersections, EPSILON},
+    ray::Ray,
+    shapes::{Object, Shape},
+    tuple::Tuple,
+};
+
+/// A cylinder of radius 1 around the y axis in object space, truncated to
+/// `minimum..maximum` (both exclusive) and capped at both ends when `closed`.
+#[derive(Debug)]
+pub struct Cylinder {
+    pub minimum: f64,
+    pub maximum: f64,
+    pub closed: bool,
+}
+
+impl Cylinder {
+    pub fn new(minimum: f64, maximum: f64, closed: bool) -> Self {
+        Self {
+            minimum,
+            maximum,
+            closed,
+        }
+    }
+    fn intersect_caps<'a>(&self, ray: &Ray, object: &'a Object, xs: &mut Vec<Intersection<'a>>) {
+        // Caps only matter when closed, and a ray parallel to them can't hit.
+        if !self.closed || ray.direction.y.abs() < EPSILON {
+            return;
+        }
+        for y in [self.minimum, self.maximum] {
+            let t = (y - ray.origin.y) / ray.direction.y;
+            if check_cap(ray, t) {
+                xs.push(Intersection::new(t, object));
+            }
+        }
+    }
+}
+impl Default for Cylinder {
+    /// An infinitely long, open cylinder.
+    fn default() -> Self {
+        Self::new(f64::NEG_INFINITY, f64::INFINITY, false)
+    }
+}
+
+/// Whether the ray at `t` is within the unit radius of the y axis.
+fn check_cap(ray: &Ray, t: f64) -> bool {
+    let x = ray.origin.x + t * ray.direction.x;
+    let z = ray.origin.z + t * ray.direction.z;
+    (x * x + z * z) <= 1.
+}
+
+impl Shape for Cylinder {
+    fn local_intersect<'a>(&'a self, ray: &Ray, object: &'a Object) -> Intersections<'a> {
+        let mut xs = Vec::new();
+        let a = ray.direction.x * ray.direction.x + ray.direction.z * ray.direction.z;
+
+        // A ray parallel to the y axis can only hit the caps.
+        if a.abs() >= EPSILON {
+            let b = 2. * ray.origin.x * ray.direction.x + 2. * ray.origin.z * ray.direction.z;
+            let c = ray.origin.x * ray.origin.x + ray.origin.z * ray.origin.z - 1.;
+            let disc = b * b - 4. * a * c;
+            if disc < 0. {
+                return Intersections::default();
+            }
+
+            let t0 = (-b - disc.sqrt()) / (2. * a);
+            let t1 = (-b + disc.sqrt()) / (2. * a);
+            for t in [t0, t1] {
+                let y = ray.origin.y + t * ray.direction.y;
+                if self.minimum < y && y < self.maximum {
+                    xs.push(Intersection::new(t, object));
+                }
+            }
+        }
+
+        self.intersect_caps(ray, object, &mut xs);
+        Intersections::new(xs)
+    }
+    fn local_normal_at(&self, point: Tuple) -> Tuple {
+        let dist = point.x * point.x + point.z * point.z;
+        if dist < 1. && point.y >= self.maximum - EPSILON {
+            Tuple::vector(0., 1., 0.)
+        } else if dist < 1. && point.y <= self.minimum + EPSILON {
+            Tuple::vector(0., -1., 0.)
+        } else {
+            Tuple::vector(point.x, 0., point.z)
+        }
+    }
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(
+            Tuple::point(-1., self.minimum, -1.),
+            Tuple::point(1., self.maximum, 1.),
+        )
+    }
+}
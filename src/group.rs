@@ -0,0 +1,78 @@
+use crate::{
+    bounds::BoundingBox,
+    intersection::Intersections,
+    matrix::Matrix4x4,
+    ray::Ray,
+    shapes::{Object, Shape},
+    tuple::Tuple,
+};
+
+/// A collection of objects that are transformed together. Each child's
+/// transform is relative to the group's object space.
+#[derive(Debug)]
+pub struct Group {
+    children: Vec<Object>,
+    world_transform: Matrix4x4,
+    /// The children's bounds, kept up to date as they are added.
+    bounds: BoundingBox,
+}
+
+impl Group {
+    pub fn new(children: Vec<Object>) -> Self {
+        let mut group = Self {
+            children: Vec::new(),
+            world_transform: Matrix4x4::_IDENTITY,
+            bounds: BoundingBox::default(),
+        };
+        for child in children {
+            group.add_child(child);
+        }
+        group
+    }
+    pub fn add_child(&mut self, mut child: Object) {
+        child.set_parent_transform(self.world_transform);
+        self.bounds.merge(&child.parent_space_bounds());
+        self.children.push(child);
+    }
+    pub fn children(&self) -> &[Object] {
+        &self.children
+    }
+}
+
+impl Shape for Group {
+    fn local_intersect<'a>(&'a self, ray: &Ray, _object: &'a Object) -> Intersections<'a> {
+        let mut xs = Intersections::default();
+        if !self.bounds.intersects(ray) {
+            return xs;
+        }
+        for child in &self.children {
+            xs.merge(child.intersect(*ray));
+        }
+        xs
+    }
+    fn intersects_before(&self, ray: &Ray, _object: &Object, distance: f64) -> bool {
+        self.bounds.intersects(ray)
+            && self
+                .children
+                .iter()
+                .any(|child| child.intersects_before(*ray, distance))
+    }
+    /// Intersections always refer to a child, so rendering never asks a
+    /// group for a normal of its own. Calling `Object::normal_at` on one
+    /// directly panics.
+    fn local_normal_at(&self, _point: Tuple) -> Tuple {
+        panic!("groups have no surface normal of their own")
+    }
+    fn bounds(&self) -> BoundingBox {
+        self.bounds
+    }
+    fn set_world_transform(&mut self, world_to_object: Matrix4x4) {
+        self.world_transform = world_to_object;
+        for child in &mut self.children {
+            child.set_parent_transform(world_to_object);
+        }
+    }
+    fn includes(&self, object: &Object) -> bool {
+        self.children.iter().any(|child| child.includes(object))
+    }
+}
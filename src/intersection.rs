@@ -0,0 +1,182 @@
+use std::ops::Index;
+
+use crate::{ray::Ray, shapes::Object, tuple::Tuple};
+
+/// How far `over_point` and `under_point` are nudged off the surface so that
+/// rays spawned from a hit don't re-intersect the surface they started on.
+pub const EPSILON: f64 = 1e-5;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Intersection<'a> {
+    t: f64,
+    object: &'a Object,
+    u: f64,
+    v: f64,
+}
+
+impl<'a> Intersection<'a> {
+    pub fn new(t: f64, object: &'a Object) -> Self {
+        Self::with_uv(t, object, 0., 0.)
+    }
+    /// An intersection that also records where on the surface it happened,
+    /// as used by triangles for normal interpolation.
+    pub fn with_uv(t: f64, object: &'a Object, u: f64, v: f64) -> Self {
+        Self { t, object, u, v }
+    }
+    pub fn t(&self) -> f64 {
+        self.t
+    }
+    pub fn object(&self) -> &'a Object {
+        self.object
+    }
+    pub fn u(&self) -> f64 {
+        self.u
+    }
+    pub fn v(&self) -> f64 {
+        self.v
+    }
+    /// `xs` are all the intersections along `ray`, including this one, and
+    /// decide which materials the ray is passing between.
+    pub fn prepare_computations(&self, ray: &Ray, xs: &Intersections) -> Computations<'a> {
+        let point = ray.position_at(self.t);
+        let eyev = -ray.direction;
+        let mut normalv = self.object.normal_at_hit(point, self);
+        let inside = Tuple::dot(normalv, eyev) < 0.;
+        if inside {
+            normalv = -normalv;
+        }
+
+        // Walk the hits up to this one, tracking which objects the ray is
+        // inside, to find the refractive indices on either side.
+        let mut containers: Vec<&Object> = Vec::new();
+        let index_of = |containers: &[&Object]| {
+            containers
+                .last()
+                .map_or(1., |object| object.material().refractive_index)
+        };
+        let (mut n1, mut n2) = (1., 1.);
+        for i in xs.iter() {
+            if i == self {
+                n1 = index_of(&containers);
+            }
+            match containers.iter().position(|&object| object == i.object) {
+                Some(position) => {
+                    containers.remove(position);
+                }
+                None => containers.push(i.object),
+            }
+            if i == self {
+                n2 = index_of(&containers);
+                break;
+            }
+        }
+
+        Computations {
+            t: self.t,
+            object: self.object,
+            point,
+            over_point: point + normalv * EPSILON,
+            under_point: point - normalv * EPSILON,
+            eyev,
+            normalv,
+            reflectv: Tuple::reflect(ray.direction, normalv),
+            inside,
+            n1,
+            n2,
+        }
+    }
+}
+
+/// Everything shading needs to know about an intersection, computed once.
+#[derive(Clone, Copy, Debug)]
+pub struct Computations<'a> {
+    pub t: f64,
+    pub object: &'a Object,
+    pub point: Tuple,
+    pub over_point: Tuple,
+    pub under_point: Tuple,
+    pub eyev: Tuple,
+    pub normalv: Tuple,
+    pub reflectv: Tuple,
+    pub inside: bool,
+    /// The refractive index of the material the ray is leaving.
+    pub n1: f64,
+    /// The refractive index of the material the ray is entering.
+    pub n2: f64,
+}
+
+impl Computations<'_> {
+    /// Schlick's approximation of the Fresnel reflectance: the fraction of
+    /// light reflected rather than refracted at this hit.
+    pub fn schlick(&self) -> f64 {
+        let mut cos = Tuple::dot(self.eyev, self.normalv);
+        if self.n1 > self.n2 {
+            let n = self.n1 / self.n2;
+            let sin2_t = n * n * (1. - cos * cos);
+            if sin2_t > 1. {
+                return 1.;
+            }
+            cos = (1. - sin2_t).sqrt();
+        }
+        let r0 = ((self.n1 - self.n2) / (self.n1 + self.n2)).powi(2);
+        r0 + (1. - r0) * (1. - cos).powi(5)
+    }
+}
+
+/// A list of intersections kept sorted by `t`.
+#[derive(Debug, Default, PartialEq)]
+pub struct Intersections<'a> {
+    xs: Vec<Intersection<'a>>,
+}
+
+impl<'a> Intersections<'a> {
+    pub fn new(mut xs: Vec<Intersection<'a>>) -> Self {
+        xs.sort_by(|a, b| a.t.total_cmp(&b.t));
+        Self { xs }
+    }
+    /// Merges in another sorted list in linear time. Containers call this
+    /// for every child they visit.
+    pub fn merge(&mut self, other: Intersections<'a>) {
+        if other.xs.is_empty() {
+            return;
+        }
+        if self.xs.is_empty() {
+            self.xs = other.xs;
+            return;
+        }
+        let mut merged = Vec::with_capacity(self.xs.len() + other.xs.len());
+        let mut left = std::mem::take(&mut self.xs).into_iter().peekable();
+        let mut right = other.xs.into_iter().peekable();
+        while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+            let next = if b.t.total_cmp(&a.t).is_lt() {
+                right.next()
+            } else {
+                left.next()
+            };
+            merged.extend(next);
+        }
+        merged.extend(left);
+        merged.extend(right);
+        self.xs = merged;
+    }
+    /// The visible intersection: the one with the lowest non-negative `t`.
+    pub fn hit(&self) -> Option<Intersection<'a>> {
+        self.xs.iter().find(|i| i.t >= 0.).copied()
+    }
+    pub fn len(&self) -> usize {
+        self.xs.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.xs.is_empty()
+    }
+    pub fn iter(&self) -> std::slice::Iter<'_, Intersection<'a>> {
+        self.xs.iter()
+    }
+}
+impl<'a> Index<usize> for Intersections<'a> {
+    type Output = Intersection<'a>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.xs[index]
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{color::Color, material::Material, shapes::Object, tuple::Tuple};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PointLight {
+    pub position: Tuple,
+    pub intensity: Color,
+}
+
+impl PointLight {
+    pub fn new(position: Tuple, intensity: Color) -> Self {
+        Self {
+            position,
+            intensity,
+        }
+    }
+}
+
+/// Phong reflection model: the sum of the ambient, diffuse and specular
+/// contributions of `light` at `point`. Only the ambient term survives when
+/// the point is `in_shadow`. `object` is only needed to place the
+/// material's pattern.
+pub fn lighting(
+    material: &Material,
+    object: &Object,
+    light: &PointLight,
+    point: Tuple,
+    eyev: Tuple,
+    normalv: Tuple,
+    in_shadow: bool,
+) -> Color {
+    let black = Color::new(0., 0., 0.);
+    let color = match &material.pattern {
+        Some(pattern) => pattern.color_at_object(object, point),
+        None => material.color,
+    };
+    let effective_color = color * light.intensity;
+    let lightv = (light.position - point).normalize();
+    let ambient = effective_color * material.ambient;
+    if in_shadow {
+        return ambient;
+    }
+
+    // A negative cosine means the light is on the other side of the surface.
+    let light_dot_normal = Tuple::dot(lightv, normalv);
+    if light_dot_normal < 0. {
+        return ambient;
+    }
+    let diffuse = effective_color * material.diffuse * light_dot_normal;
+
+    // A negative cosine means the light reflects away from the eye.
+    let reflectv = Tuple::reflect(-lightv, normalv);
+    let reflect_dot_eye = Tuple::dot(reflectv, eyev);
+    let specular = if reflect_dot_eye <= 0. {
+        black
+    } else {
+        let factor = reflect_dot_eye.powf(material.shininess);
+        light.intensity * material.specular * factor
+    };
+
+    ambient + diffuse + specular
+}
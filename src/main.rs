@@ -1,11 +1,54 @@
+// `main` doesn't render anything yet, so these modules are only used by the
+// tests and demo scenes.
+#[cfg_attr(not(test), allow(dead_code))]
 mod tuple;
+#[cfg_attr(not(test), allow(dead_code))]
 mod color;
+#[cfg_attr(not(test), allow(dead_code))]
 mod canvas;
 mod tests;
+#[cfg_attr(not(test), allow(dead_code))]
 mod matrix;
+#[cfg_attr(not(test), allow(dead_code))]
 mod ray;
+#[cfg_attr(not(test), allow(dead_code))]
 mod shapes;
-mod hit;
+#[cfg_attr(not(test), allow(dead_code))]
+mod plane;
+#[cfg_attr(not(test), allow(dead_code))]
+mod cube;
+#[cfg_attr(not(test), allow(dead_code))]
+mod cylinder;
+#[cfg_attr(not(test), allow(dead_code))]
+mod cone;
+#[cfg_attr(not(test), allow(dead_code))]
+mod triangle;
+#[cfg_attr(not(test), allow(dead_code))]
+mod obj;
+#[cfg_attr(not(test), allow(dead_code))]
+mod group;
+#[cfg_attr(not(test), allow(dead_code))]
+mod csg;
+#[cfg_attr(not(test), allow(dead_code))]
+mod bounds;
+#[cfg_attr(not(test), allow(dead_code))]
+mod bvh;
+#[cfg_attr(not(test), allow(dead_code))]
+mod intersection;
+#[cfg_attr(not(test), allow(dead_code))]
+mod material;
+#[cfg_attr(not(test), allow(dead_code))]
+mod pattern;
+#[cfg_attr(not(test), allow(dead_code))]
+mod noise;
+#[cfg_attr(not(test), allow(dead_code))]
+mod uv;
+#[cfg_attr(not(test), allow(dead_code))]
+mod light;
+#[cfg_attr(not(test), allow(dead_code))]
+mod world;
+#[cfg_attr(not(test), allow(dead_code))]
+mod camera;
 
 fn main() {
     println!("Hello, world!");
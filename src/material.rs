@@ -0,0 +1,40 @@
+use crate::{color::Color, pattern::Texture};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Material {
+    pub color: Color,
+    /// Used instead of `color` when set.
+    pub pattern: Option<Texture>,
+    pub ambient: f64,
+    pub diffuse: f64,
+    pub specular: f64,
+    pub shininess: f64,
+    /// 0 for a matte surface, 1 for a perfect mirror.
+    pub reflective: f64,
+    /// 0 for an opaque surface, 1 for a fully transparent one.
+    pub transparency: f64,
+    /// How much light bends entering the material: 1 for a vacuum, about
+    /// 1.5 for glass.
+    pub refractive_index: f64,
+}
+
+impl Material {
+    pub fn new(color: Color, ambient: f64, diffuse: f64, specular: f64, shininess: f64) -> Self {
+        Self {
+            color,
+            pattern: None,
+            ambient,
+            diffuse,
+            specular,
+            shininess,
+            reflective: 0.,
+            transparency: 0.,
+            refractive_index: 1.,
+        }
+    }
+}
+impl Default for Material {
+    fn default() -> Self {
+        Self::new(Color::new(1., 1., 1.), 0.1, 0.9, 0.9, 200.)
+    }
+}
@@ -19,8 +19,8 @@ impl Matrix2x2 {
     pub fn inverse(&self) -> Self {
         let determinant_inv = 1.0 / self.determinant();
         Self::new([
-            [self[1][1] * determinant_inv, -self[1][0] * determinant_inv],
-            [-self[0][1] * determinant_inv, self[0][0] * determinant_inv],
+            [self[1][1] * determinant_inv, -self[0][1] * determinant_inv],
+            [-self[1][0] * determinant_inv, self[0][0] * determinant_inv],
         ])
     }
 }
@@ -113,10 +113,10 @@ impl Matrix3x3 {
     pub fn inverse(&self) -> Self {
         let d = self.determinant();
         let mut data = [[0.0; 3]; 3];
-        for i in 0..3 {
-            for j in 0..3 {
-                let c = self.cofactor(i, j);
-                data[j][i] = c / d;
+        for (i, row) in data.iter_mut().enumerate() {
+            for (j, item) in row.iter_mut().enumerate() {
+                let c = self.cofactor(j, i);
+                *item = c / d;
             }
         }
         Matrix3x3::new(data)
@@ -223,6 +223,21 @@ impl Matrix4x4 {
             ],
         }
     }
+    /// Orients the world relative to an eye at `from` looking at `to`.
+    pub fn view_transform(from: Tuple, to: Tuple, up: Tuple) -> Self {
+        let forward = (to - from).normalize();
+        let left = Tuple::cross(forward, up.normalize());
+        let true_up = Tuple::cross(left, forward);
+        let orientation = Self {
+            data: [
+                [left.x, left.y, left.z, 0.],
+                [true_up.x, true_up.y, true_up.z, 0.],
+                [-forward.x, -forward.y, -forward.z, 0.],
+                [0., 0., 0., 1.],
+            ],
+        };
+        orientation * Self::translation(-from.x, -from.y, -from.z)
+    }
     pub fn new(data: [[f64; 4]; 4]) -> Self {
         Self { data }
     }
@@ -278,7 +293,7 @@ impl Matrix4x4 {
         let mut data = [[0.0; 4]; 4];
         for (i, row) in data.iter_mut().enumerate() {
             for (j, item) in row.iter_mut().enumerate() {
-                let c = self.cofactor(i, j);
+                let c = self.cofactor(j, i);
                 *item = c / d;
             }
         }
@@ -0,0 +1,280 @@
+use crate::tuple::Tuple;
+
+/// Ken Perlin's reference permutation of 0..256.
+const PERMUTATION: [u8; 256] = [
+    151, 160, 137, 91, 90, 15, 131, 13, 201, 95, 96, 53, 194, 233, 7, 225, 140, 36, 103, 30, 69,
+    142, 8, 99, 37, 240, 21, 10, 23, 190, 6, 148, 247, 120, 234, 75, 0, 26, 197, 62, 94, 252, 219,
+    203, 117, 35, 11, 32, 57, 177, 33, 88, 237, 149, 56, 87, 174, 20, 125, 136, 171, 168, 68, 175,
+    74, 165, 71, 134, 139, 48, 27, 166, 77, 146, 158, 231, 83, 111, 229, 122, 60, 211, 133, 230,
+    220, 105, 92, 41, 55, 46, 245, 40, 244, 102, 143, 54, 65, 25, 63, 161, 1, 216, 80, 73, 209, 76,
+    132, 187, 208, 89, 18, 169, 200, 196, 135, 130, 116, 188, 159, 86, 164, 100, 109, 198, 173,
+    186, 3, 64, 52, 217, 226, 250, 124, 123, 5, 202, 38, 147, 118, 126, 255, 82, 85, 212, 207, 206,
+    59, 227, 47, 16, 58, 17, 182, 189, 28, 42, 223, 183, 170, 213, 119, 248, 152, 2, 44, 154, 163,
+    70, 221, 153, 101, 155, 167, 43, 172, 9, 129, 22, 39, 253, 19, 98, 108, 110, 79, 113, 224, 232,
+    178, 185, 112, 104, 218, 246, 97, 228, 251, 34, 242, 193, 238, 210, 144, 12, 191, 179, 162,
+    241, 81, 51, 145, 235, 249, 14, 239, 107, 49, 192, 214, 31, 181, 199, 106, 157, 184, 84, 204,
+    176, 115, 121, 50, 45, 127, 4, 150, 254, 138, 236, 205, 93, 222, 114, 67, 29, 24, 72, 243, 141,
+    128, 195, 78, 66, 215, 61, 156, 180,
+];
+
+/// A deterministic source of 3D gradient noise. Noise with the same seed
+/// is identical across runs and platforms; the default uses Ken Perlin's
+/// reference permutation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Noise {
+    permutation: [u8; 256],
+}
+
+impl Noise {
+    pub fn new(seed: u64) -> Self {
+        let mut permutation = [0; 256];
+        for (i, p) in permutation.iter_mut().enumerate() {
+            *p = i as u8;
+        }
+        // Fisher-Yates, driven by splitmix64 so that nothing depends on a
+        // platform random number generator.
+        let mut state = seed;
+        for i in (1..256).rev() {
+            let j = (splitmix64(&mut state) % (i as u64 + 1)) as usize;
+            permutation.swap(i, j);
+        }
+        Self { permutation }
+    }
+    fn hash(&self, i: usize) -> usize {
+        self.permutation[i & 255] as usize
+    }
+    /// Ken Perlin's improved gradient noise. Varies smoothly between roughly
+    /// -1 and 1, and is zero at every integer lattice point.
+    pub fn perlin(&self, point: Tuple) -> f64 {
+        let p = |i: usize| self.hash(i);
+        // The unit cube containing the point, and where the point sits in it.
+        let (xf, yf, zf) = (point.x.floor(), point.y.floor(), point.z.floor());
+        let (x, y, z) = (point.x - xf, point.y - yf, point.z - zf);
+        let (xi, yi, zi) = (lattice(xf), lattice(yf), lattice(zf));
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+
+        // Hash each of the cube's corners.
+        let a = p(xi) + yi;
+        let (aa, ab) = (p(a) + zi, p(a + 1) + zi);
+        let b = p(xi + 1) + yi;
+        let (ba, bb) = (p(b) + zi, p(b + 1) + zi);
+
+        lerp(
+            w,
+            lerp(
+                v,
+                lerp(u, grad(p(aa), x, y, z), grad(p(ba), x - 1., y, z)),
+                lerp(u, grad(p(ab), x, y - 1., z), grad(p(bb), x - 1., y - 1., z)),
+            ),
+            lerp(
+                v,
+                lerp(
+                    u,
+                    grad(p(aa + 1), x, y, z - 1.),
+                    grad(p(ba + 1), x - 1., y, z - 1.),
+                ),
+                lerp(
+                    u,
+                    grad(p(ab + 1), x, y - 1., z - 1.),
+                    grad(p(bb + 1), x - 1., y - 1., z - 1.),
+                ),
+            ),
+        )
+    }
+    /// Simplex noise, after Stefan Gustavson's reference implementation.
+    /// Cheaper than Perlin noise and without its axis-aligned artifacts,
+    /// varying between roughly -1 and 1.
+    pub fn simplex(&self, point: Tuple) -> f64 {
+        const F3: f64 = 1. / 3.;
+        const G3: f64 = 1. / 6.;
+        // Skew space so the simplices become cubes, to find the simplex
+        // cell the point is in.
+        let s = (point.x + point.y + point.z) * F3;
+        let (i, j, k) = (
+            (point.x + s).floor(),
+            (point.y + s).floor(),
+            (point.z + s).floor(),
+        );
+        let t = (i + j + k) * G3;
+        let first = point - Tuple::point(i - t, j - t, k - t);
+
+        // Which of the six tetrahedra in the cube the point is in decides
+        // the order the other corners are visited in.
+        let (x, y, z) = (first.x, first.y, first.z);
+        let (second, third) = if x >= y {
+            if y >= z {
+                ((1, 0, 0), (1, 1, 0))
+            } else if x >= z {
+                ((1, 0, 0), (1, 0, 1))
+            } else {
+                ((0, 0, 1), (1, 0, 1))
+            }
+        } else if y < z {
+            ((0, 0, 1), (0, 1, 1))
+        } else if x < z {
+            ((0, 1, 0), (0, 1, 1))
+        } else {
+            ((0, 1, 0), (1, 1, 0))
+        };
+
+        let (ii, jj, kk) = (lattice(i), lattice(j), lattice(k));
+        let corner = |(di, dj, dk): (usize, usize, usize), n: f64| {
+            let offset = Tuple::vector(
+                x - di as f64 + n * G3,
+                y - dj as f64 + n * G3,
+                z - dk as f64 + n * G3,
+            );
+            // A radius of 0.5 keeps each corner's influence inside the
+            // simplices that share it. The reference implementation's 0.6
+            // leaves visible seams.
+            let t = 0.5 - Tuple::dot(offset, offset);
+            if t < 0. {
+                return 0.;
+            }
+            let h = self.hash(ii + di + self.hash(jj + dj + self.hash(kk + dk)));
+            t.powi(4) * grad(h, offset.x, offset.y, offset.z)
+        };
+        // Scaled so the result spans roughly -1..1.
+        76. * (corner((0, 0, 0), 0.)
+            + corner(second, 1.)
+            + corner(third, 2.)
+            + corner((1, 1, 1), 3.))
+    }
+    fn basis(&self, basis: Basis, point: Tuple) -> f64 {
+        match basis {
+            Basis::Perlin => self.perlin(point),
+            Basis::Simplex => self.simplex(point),
+        }
+    }
+    /// Calls `octave` with the noise of each octave and its amplitude, and
+    /// returns the sum of the amplitudes.
+    fn octaves(&self, point: Tuple, fractal: &Fractal, mut octave: impl FnMut(f64, f64)) -> f64 {
+        let (mut frequency, mut amplitude, mut total) = (1., 1., 0.);
+        for _ in 0..fractal.octaves {
+            let p = Tuple::point(
+                point.x * frequency,
+                point.y * frequency,
+                point.z * frequency,
+            );
+            octave(self.basis(fractal.basis, p), amplitude);
+            total += amplitude;
+            frequency *= fractal.lacunarity;
+            amplitude *= fractal.gain;
+        }
+        total
+    }
+    /// Fractal Brownian motion: octaves of noise at rising frequencies and
+    /// falling amplitudes, for clouds and terrain. Normalized to the basis
+    /// noise's range of roughly -1..1.
+    pub fn fbm(&self, point: Tuple, fractal: &Fractal) -> f64 {
+        let mut sum = 0.;
+        let total = self.octaves(point, fractal, |n, amplitude| sum += n * amplitude);
+        if total > 0. {
+            sum / total
+        } else {
+            0.
+        }
+    }
+    /// Like `fbm`, but summing the absolute value of each octave, which
+    /// creases the noise wherever it crosses zero. In 0..1, for marble and
+    /// flames.
+    pub fn turbulence(&self, point: Tuple, fractal: &Fractal) -> f64 {
+        let mut sum = 0.;
+        let total = self.octaves(point, fractal, |n, amplitude| sum += n.abs() * amplitude);
+        if total > 0. {
+            (sum / total).min(1.)
+        } else {
+            0.
+        }
+    }
+    /// Ridged multifractal noise: inverted turbulence with sharp ridges, each
+    /// octave weighted by the one before so detail gathers on the ridges.
+    /// In 0..1, for mountain ranges.
+    pub fn ridged(&self, point: Tuple, fractal: &Fractal) -> f64 {
+        let (mut sum, mut weight) = (0., 1.);
+        let total = self.octaves(point, fractal, |n, amplitude| {
+            let signal = (1. - n.abs()).max(0.).powi(2) * weight;
+            weight = (signal * 2.).clamp(0., 1.);
+            sum += signal * amplitude;
+        });
+        if total > 0. {
+            sum / total
+        } else {
+            0.
+        }
+    }
+}
+impl Default for Noise {
+    fn default() -> Self {
+        Self {
+            permutation: PERMUTATION,
+        }
+    }
+}
+
+/// Which gradient noise the fractal helpers are built from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Basis {
+    Perlin,
+    Simplex,
+}
+
+/// How the fractal helpers combine octaves of noise. Each octave's
+/// frequency is `lacunarity` times the last one's and its amplitude `gain`
+/// times the last one's.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fractal {
+    pub basis: Basis,
+    pub octaves: usize,
+    pub lacunarity: f64,
+    pub gain: f64,
+}
+
+impl Default for Fractal {
+    fn default() -> Self {
+        Self {
+            basis: Basis::Perlin,
+            octaves: 6,
+            lacunarity: 2.,
+            gain: 0.5,
+        }
+    }
+}
+
+/// One step of the splitmix64 generator.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// A lattice coordinate wrapped into the permutation's 0..256.
+fn lattice(coordinate: f64) -> usize {
+    (coordinate as i64 & 255) as usize
+}
+
+/// 6t^5 - 15t^4 + 10t^3, which eases in and out of each lattice cell.
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6. - 15.) + 10.)
+}
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+    a + t * (b - a)
+}
+
+/// The dot product of the offset `(x, y, z)` with one of twelve gradient
+/// directions picked by `hash`.
+fn grad(hash: usize, x: f64, y: f64, z: f64) -> f64 {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 {
+        y
+    } else if h == 12 || h == 14 {
+        x
+    } else {
+        z
+    };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
@@ -0,0 +1,188 @@
+use std::{fmt::Display, fs, io};
+
+use crate::{
+    group::Group,
+    material::Material,
+    matrix::Matrix4x4,
+    shapes::Object,
+    triangle::{SmoothTriangle, Triangle},
+    tuple::Tuple,
+};
+
+#[derive(Debug)]
+pub enum ObjError {
+    Io(io::Error),
+    /// A malformed statement, with the 1-based line it appeared on.
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+impl Display for ObjError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjError::Io(e) => write!(f, "failed to read OBJ file: {}", e),
+            ObjError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+impl std::error::Error for ObjError {}
+impl From<io::Error> for ObjError {
+    fn from(e: io::Error) -> Self {
+        ObjError::Io(e)
+    }
+}
+
+/// The geometry read from a Wavefront OBJ file. Faces outside of any `g`
+/// statement land in `default_group`.
+#[derive(Debug, Default)]
+pub struct ObjFile {
+    pub vertices: Vec<Tuple>,
+    pub normals: Vec<Tuple>,
+    pub default_group: Vec<Object>,
+    pub groups: Vec<(String, Vec<Object>)>,
+    /// How many lines were skipped because they aren't supported.
+    pub ignored: usize,
+}
+
+impl ObjFile {
+    pub fn read(path: &str) -> Result<Self, ObjError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+    pub fn parse(source: &str) -> Result<Self, ObjError> {
+        let mut obj = ObjFile::default();
+        for (i, line) in source.lines().enumerate() {
+            let line_number = i + 1;
+            let error = |message: String| ObjError::Parse {
+                line: line_number,
+                message,
+            };
+            let mut words = line.split_whitespace();
+            match words.next() {
+                None => {}
+                Some(word) if word.starts_with('#') => {}
+                Some("v") => {
+                    let [x, y, z] = parse_coordinates(words).map_err(error)?;
+                    obj.vertices.push(Tuple::point(x, y, z));
+                }
+                Some("vn") => {
+                    let [x, y, z] = parse_coordinates(words).map_err(error)?;
+                    obj.normals.push(Tuple::vector(x, y, z));
+                }
+                Some("f") => {
+                    let triangles = obj.parse_face(words).map_err(error)?;
+                    match obj.groups.last_mut() {
+                        Some((_, group)) => group.extend(triangles),
+                        None => obj.default_group.extend(triangles),
+                    }
+                }
+                Some("g") => {
+                    let name = words.collect::<Vec<_>>().join(" ");
+                    obj.groups.push((name, Vec::new()));
+                }
+                Some(_) => obj.ignored += 1,
+            }
+        }
+        Ok(obj)
+    }
+    /// The whole model as one group, with a child group for each named
+    /// group in the file.
+    pub fn into_group(self, transform: Matrix4x4) -> Object {
+        let mut group = Group::new(self.default_group);
+        for (_, triangles) in self.groups {
+            group.add_child(Object::new(
+                Group::new(triangles),
+                Matrix4x4::_IDENTITY,
+                Material::default(),
+            ));
+        }
+        Object::new(group, transform, Material::default())
+    }
+    /// Every triangle in the file, ready to be added to a world.
+    pub fn into_objects(self) -> Vec<Object> {
+        let mut objects = self.default_group;
+        for (_, group) in self.groups {
+            objects.extend(group);
+        }
+        objects
+    }
+    /// Triangulates a face as a fan around its first vertex. Faces give
+    /// vertices as `v`, `v/vt`, `v//vn` or `v/vt/vn`, and only become smooth
+    /// triangles when every vertex has a normal.
+    fn parse_face<'a>(&self, words: impl Iterator<Item = &'a str>) -> Result<Vec<Object>, String> {
+        let mut points = Vec::new();
+        let mut normals = Vec::new();
+        for word in words {
+            let mut indices = word.split('/');
+            let vertex = indices.next().unwrap_or_default();
+            points.push(lookup(&self.vertices, vertex, "vertex")?);
+            match indices.nth(1) {
+                Some(normal) if !normal.is_empty() => {
+                    normals.push(lookup(&self.normals, normal, "normal")?)
+                }
+                _ => {}
+            }
+        }
+        if points.len() < 3 {
+            return Err(format!(
+                "face needs at least 3 vertices, found {}",
+                points.len()
+            ));
+        }
+        let smooth = normals.len() == points.len();
+
+        let mut triangles = Vec::new();
+        for i in 1..points.len() - 1 {
+            let triangle = if smooth {
+                Object::new(
+                    SmoothTriangle::new(
+                        points[0],
+                        points[i],
+                        points[i + 1],
+                        normals[0],
+                        normals[i],
+                        normals[i + 1],
+                    ),
+                    Matrix4x4::_IDENTITY,
+                    Material::default(),
+                )
+            } else {
+                Object::new(
+                    Triangle::new(points[0], points[i], points[i + 1]),
+                    Matrix4x4::_IDENTITY,
+                    Material::default(),
+                )
+            };
+            triangles.push(triangle);
+        }
+        Ok(triangles)
+    }
+}
+
+fn parse_coordinates<'a>(mut words: impl Iterator<Item = &'a str>) -> Result<[f64; 3], String> {
+    let mut coordinates = [0.; 3];
+    for c in coordinates.iter_mut() {
+        let word = words.next().ok_or("expected 3 coordinates")?;
+        *c = word
+            .parse()
+            .map_err(|_| format!("invalid coordinate '{}'", word))?;
+    }
+    Ok(coordinates)
+}
+
+/// Resolves a 1-based OBJ index, where negative indices count back from the
+/// most recent entry.
+fn lookup(list: &[Tuple], word: &str, kind: &str) -> Result<Tuple, String> {
+    let index: i64 = word
+        .parse()
+        .map_err(|_| format!("invalid {} index '{}'", kind, word))?;
+    let resolved = match index {
+        i if i > 0 => i - 1,
+        i if i < 0 => list.len() as i64 + i,
+        _ => -1,
+    };
+    if resolved < 0 || resolved >= list.len() as i64 {
+        return Err(format!("{} index {} is out of range", kind, index));
+    }
+    Ok(list[resolved as usize])
+}
@@ -0,0 +1,215 @@
+use std::{fmt::Debug, rc::Rc};
+
+use crate::{color::Color, matrix::Matrix4x4, noise::Noise, shapes::Object, tuple::Tuple};
+
+/// A color that varies over space. `Texture` takes care of moving points
+/// from world space into the pattern's own space.
+pub trait Pattern: Debug {
+    /// The color at a point that has already been transformed into pattern
+    /// space.
+    fn local_color_at(&self, point: Tuple) -> Color;
+}
+
+/// A pattern as applied to a material. Cloning is cheap, as the pattern
+/// itself is shared.
+#[derive(Clone, Debug)]
+pub struct Texture {
+    transform: Matrix4x4,
+    pattern: Rc<dyn Pattern>,
+}
+
+impl Texture {
+    /// `transform` maps the object space of whatever the texture is applied
+    /// to into the pattern's space.
+    pub fn new(pattern: impl Pattern + 'static, transform: Matrix4x4) -> Self {
+        Self {
+            transform,
+            pattern: Rc::new(pattern),
+        }
+    }
+    pub fn transform(&self) -> Matrix4x4 {
+        self.transform
+    }
+    pub fn set_transform(&mut self, transform: Matrix4x4) {
+        self.transform = transform;
+    }
+    pub fn pattern(&self) -> &dyn Pattern {
+        self.pattern.as_ref()
+    }
+    /// The color at a point in the textured object's space.
+    pub fn color_at(&self, object_point: Tuple) -> Color {
+        self.pattern.local_color_at(self.transform * object_point)
+    }
+    /// The color at a world space point on `object`.
+    pub fn color_at_object(&self, object: &Object, world_point: Tuple) -> Color {
+        self.color_at(object.world_to_object(world_point))
+    }
+}
+/// A flat color, so it can stand in wherever a texture is expected.
+impl From<Color> for Texture {
+    fn from(color: Color) -> Self {
+        Self::new(Solid(color), Matrix4x4::_IDENTITY)
+    }
+}
+/// Textures are equal when they share a pattern and a transform.
+impl PartialEq for Texture {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.pattern, &other.pattern) && self.transform == other.transform
+    }
+}
+
+/// A single color everywhere.
+#[derive(Debug)]
+pub struct Solid(pub Color);
+
+impl Pattern for Solid {
+    fn local_color_at(&self, _point: Tuple) -> Color {
+        self.0
+    }
+}
+
+// The two-way patterns below choose between sub-textures rather than plain
+// colors, so they nest. Each sub-texture's transform is relative to the
+// space of the pattern holding it.
+
+/// Declares a pattern struct with sub-textures `a` and `b`, and a `new`
+/// that accepts anything convertible into a texture for each.
+macro_rules! two_texture_pattern {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug)]
+        pub struct $name {
+            pub a: Texture,
+            pub b: Texture,
+        }
+
+        impl $name {
+            pub fn new(a: impl Into<Texture>, b: impl Into<Texture>) -> Self {
+                Self {
+                    a: a.into(),
+                    b: b.into(),
+                }
+            }
+        }
+    };
+}
+
+two_texture_pattern!(
+    /// Alternates between `a` and `b` every unit along x.
+    Stripe
+);
+
+impl Pattern for Stripe {
+    fn local_color_at(&self, point: Tuple) -> Color {
+        if point.x.floor().rem_euclid(2.) == 0. {
+            self.a.color_at(point)
+        } else {
+            self.b.color_at(point)
+        }
+    }
+}
+
+two_texture_pattern!(
+    /// Blends linearly from `a` to `b` across each unit along x.
+    Gradient
+);
+
+impl Pattern for Gradient {
+    fn local_color_at(&self, point: Tuple) -> Color {
+        let fraction = point.x - point.x.floor();
+        let (a, b) = (self.a.color_at(point), self.b.color_at(point));
+        a + (b - a) * fraction
+    }
+}
+
+two_texture_pattern!(
+    /// Concentric rings around the y axis, alternating every unit.
+    Ring
+);
+
+impl Pattern for Ring {
+    fn local_color_at(&self, point: Tuple) -> Color {
+        let distance = (point.x * point.x + point.z * point.z).sqrt();
+        if distance.floor().rem_euclid(2.) == 0. {
+            self.a.color_at(point)
+        } else {
+            self.b.color_at(point)
+        }
+    }
+}
+
+two_texture_pattern!(
+    /// Unit cubes alternating between `a` and `b` in all three dimensions.
+    Checkers
+);
+
+impl Pattern for Checkers {
+    fn local_color_at(&self, point: Tuple) -> Color {
+        let sum = point.x.floor() + point.y.floor() + point.z.floor();
+        if sum.rem_euclid(2.) == 0. {
+            self.a.color_at(point)
+        } else {
+            self.b.color_at(point)
+        }
+    }
+}
+
+two_texture_pattern!(
+    /// Blends linearly from `a` to `b` across each unit of distance from the y
+    /// axis.
+    RadialGradient
+);
+
+impl Pattern for RadialGradient {
+    fn local_color_at(&self, point: Tuple) -> Color {
+        let distance = (point.x * point.x + point.z * point.z).sqrt();
+        let fraction = distance - distance.floor();
+        let (a, b) = (self.a.color_at(point), self.b.color_at(point));
+        a + (b - a) * fraction
+    }
+}
+
+two_texture_pattern!(
+    /// The average of two textures.
+    Blend
+);
+
+impl Pattern for Blend {
+    fn local_color_at(&self, point: Tuple) -> Color {
+        (self.a.color_at(point) + self.b.color_at(point)) * 0.5
+    }
+}
+
+/// Another texture with its input point jittered by up to `scale` units of
+/// Perlin noise on each axis, for a more organic look.
+#[derive(Debug)]
+pub struct Perturbed {
+    pub pattern: Texture,
+    pub scale: f64,
+    pub noise: Noise,
+}
+
+impl Perturbed {
+    pub fn new(pattern: impl Into<Texture>, scale: f64) -> Self {
+        Self {
+            pattern: pattern.into(),
+            scale,
+            noise: Noise::default(),
+        }
+    }
+}
+
+impl Pattern for Perturbed {
+    fn local_color_at(&self, point: Tuple) -> Color {
+        // Offsetting the samples gives each axis its own, uncorrelated noise.
+        let offset = |dx: f64, dy: f64, dz: f64| {
+            self.noise.perlin(point + Tuple::vector(dx, dy, dz)) * self.scale
+        };
+        let jitter = Tuple::vector(
+            offset(0., 0., 0.),
+            offset(31.4, 15.9, 26.5),
+            offset(-35.8, 97.9, -32.3),
+        );
+        self.pattern.color_at(point + jitter)
+    }
+}
@@ -0,0 +1,32 @@
+use crate::{
+    bounds::BoundingBox,
+    intersection::{Intersection, Intersections, EPSILON},
+    ray::Ray,
+    shapes::{Object, Shape},
+    tuple::Tuple,
+};
+
+/// The infinite xz plane in object space.
+#[derive(Debug)]
+pub struct Plane;
+
+impl Shape for Plane {
+    fn local_intersect<'a>(&'a self, ray: &Ray, object: &'a Object) -> Intersections<'a> {
+        // A ray parallel to the plane never meets it, and a coplanar ray is
+        // treated as a miss since the plane is infinitely thin.
+        if ray.direction.y.abs() < EPSILON {
+            return Intersections::default();
+        }
+        let t = -ray.origin.y / ray.direction.y;
+        Intersections::new(vec![Intersection::new(t, object)])
+    }
+    fn local_normal_at(&self, _point: Tuple) -> Tuple {
+        Tuple::vector(0., 1., 0.)
+    }
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(
+            Tuple::point(f64::NEG_INFINITY, 0., f64::NEG_INFINITY),
+            Tuple::point(f64::INFINITY, 0., f64::INFINITY),
+        )
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{tuple::Tuple, matrix::Matrix4x4};
 
+#[derive(Clone, Copy, Debug)]
 pub struct Ray {
     pub origin: Tuple,
     pub direction: Tuple,
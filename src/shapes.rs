@@ -1,33 +1,202 @@
-use crate::{hit::Hit, matrix::Matrix4x4, ray::Ray, tuple::Tuple};
+use std::{any::Any, fmt::Debug};
 
-#[derive(Debug, PartialEq)]
-pub enum Shapes {
-    Sphere { transform: Matrix4x4 },
+use crate::{
+    bounds::BoundingBox,
+    intersection::{Intersection, Intersections},
+    material::Material,
+    matrix::Matrix4x4,
+    ray::Ray,
+    tuple::Tuple,
+};
+
+/// A primitive in its own object space. `Object` takes care of moving rays
+/// and normals between world space and object space.
+pub trait Shape: Any + Debug {
+    /// Intersections with a ray that has already been transformed into
+    /// object space. `object` is the wrapper the intersections refer to.
+    fn local_intersect<'a>(&'a self, ray: &Ray, object: &'a Object) -> Intersections<'a>;
+    /// The normal at a point that has already been transformed into object
+    /// space. Containers, which are never the object of an intersection,
+    /// panic here.
+    fn local_normal_at(&self, point: Tuple) -> Tuple;
+    /// The box enclosing the shape in its own object space.
+    fn bounds(&self) -> BoundingBox;
+    /// The normal at the point where `hit` struck the shape. Only shapes that
+    /// vary their normal across the surface need more than `local_normal_at`.
+    fn local_normal_at_hit(&self, point: Tuple, _hit: &Intersection) -> Tuple {
+        self.local_normal_at(point)
+    }
+    /// Whether `ray` hits the shape at some `t` between zero and `distance`.
+    /// Containers override this to stop at the first such hit.
+    fn intersects_before(&self, ray: &Ray, object: &Object, distance: f64) -> bool {
+        self.local_intersect(ray, object)
+            .hit()
+            .is_some_and(|hit| hit.t() < distance)
+    }
+    /// Called whenever the owning object's world-to-object transform changes,
+    /// so that containers can pass it down to their children.
+    fn set_world_transform(&mut self, _world_to_object: Matrix4x4) {}
+    /// Whether `object` is one of this shape's descendants.
+    fn includes(&self, _object: &Object) -> bool {
+        false
+    }
+}
+
+#[derive(Debug)]
+pub struct Object {
+    transform: Matrix4x4,
+    /// Maps world space into the space of the group holding this object.
+    parent_transform: Matrix4x4,
+    /// `transform` combined with every parent transform up to the world.
+    world_transform: Matrix4x4,
+    material: Material,
+    shape: Box<dyn Shape>,
+}
+
+impl Object {
+    /// `transform` maps the parent's space (world space, unless the object
+    /// is added to a group) into the shape's object space.
+    pub fn new(shape: impl Shape + 'static, transform: Matrix4x4, material: Material) -> Self {
+        let mut object = Self {
+            transform,
+            parent_transform: Matrix4x4::_IDENTITY,
+            world_transform: transform,
+            material,
+            shape: Box::new(shape),
+        };
+        object.shape.set_world_transform(transform);
+        object
+    }
+    pub fn sphere(transform: Matrix4x4, material: Material) -> Self {
+        Self::new(Sphere, transform, material)
+    }
+    pub fn transform(&self) -> Matrix4x4 {
+        self.transform
+    }
+    pub fn set_transform(&mut self, transform: Matrix4x4) {
+        self.transform = transform;
+        self.update_world_transform();
+    }
+    /// Records the combined transform of the groups this object sits in.
+    pub fn set_parent_transform(&mut self, parent_transform: Matrix4x4) {
+        self.parent_transform = parent_transform;
+        self.update_world_transform();
+    }
+    fn update_world_transform(&mut self) {
+        self.world_transform = self.transform * self.parent_transform;
+        self.shape.set_world_transform(self.world_transform);
+    }
+    pub fn material(&self) -> &Material {
+        &self.material
+    }
+    pub fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+    pub fn shape(&self) -> &dyn Shape {
+        self.shape.as_ref()
+    }
+    /// The wrapped primitive, if it is a `T`.
+    pub fn shape_as<T: Shape>(&self) -> Option<&T> {
+        (self.shape.as_ref() as &dyn Any).downcast_ref()
+    }
+    pub fn shape_as_mut<T: Shape>(&mut self) -> Option<&mut T> {
+        (self.shape.as_mut() as &mut dyn Any).downcast_mut()
+    }
+    /// Whether `object` is this object or one of its descendants.
+    pub fn includes(&self, object: &Object) -> bool {
+        self == object || self.shape.includes(object)
+    }
+    /// The shape's bounds in the space of its parent, or world space for a
+    /// top-level object.
+    pub fn parent_space_bounds(&self) -> BoundingBox {
+        // `transform` maps parent space into object space, so the bounds go
+        // the other way.
+        self.shape.bounds().transform(self.transform.inverse())
+    }
+    /// Intersections with a ray given in the parent's space.
+    pub fn intersect(&self, ray: Ray) -> Intersections<'_> {
+        self.shape
+            .local_intersect(&ray.transform(self.transform), self)
+    }
+    /// Whether a ray given in the parent's space hits the object before
+    /// `distance`, which is all a shadow ray needs to know.
+    pub fn intersects_before(&self, ray: Ray, distance: f64) -> bool {
+        self.shape
+            .intersects_before(&ray.transform(self.transform), self, distance)
+    }
+    /// The world space normal at a point on the object's surface.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the object is a group, CSG or BVH. Containers have no
+    /// surface of their own; ask the object an intersection refers to.
+    pub fn normal_at(&self, world_point: Tuple) -> Tuple {
+        self.normal_to_world(
+            self.shape
+                .local_normal_at(self.world_to_object(world_point)),
+        )
+    }
+    /// Like `normal_at`, but lets the shape use where `hit` struck it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the object is a container, like `normal_at`.
+    pub fn normal_at_hit(&self, world_point: Tuple, hit: &Intersection) -> Tuple {
+        self.normal_to_world(
+            self.shape
+                .local_normal_at_hit(self.world_to_object(world_point), hit),
+        )
+    }
+    /// Converts a world space point into object space, through every group
+    /// the object is nested in.
+    pub fn world_to_object(&self, point: Tuple) -> Tuple {
+        self.world_transform * point
+    }
+    /// Converts an object space normal back into world space, through every
+    /// group the object is nested in.
+    pub fn normal_to_world(&self, normal: Tuple) -> Tuple {
+        // `world_transform` already maps world space into object space, so
+        // its transpose is the inverse-transpose needed for normals.
+        let mut world_normal = self.world_transform.transpose() * normal;
+        world_normal.w = 0.;
+        world_normal.normalize()
+    }
+}
+/// Objects are compared by identity, which is what intersections need.
+impl PartialEq for Object {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
 }
 
-impl Shapes {
-    pub fn sphere(transform: Matrix4x4) -> Self {
-        Self::Sphere { transform: transform }
-    }
-    pub fn intersect(&self, ray: Ray) -> Option<Hit> {
-        match self {
-            Shapes::Sphere { transform } => {
-                let r = ray.transform(*transform);
-                let sphere_to_ray = r.origin - Tuple::point(0., 0., 0.);
-                let a = Tuple::dot(r.direction, r.direction);
-                let b = 2. * Tuple::dot(r.direction, sphere_to_ray);
-                let c = Tuple::dot(sphere_to_ray, sphere_to_ray) - 1.;
-                let d = (b * b) - (4. * a * c);
-
-                if d >= 0. {
-                    let t1 = (-b - d.sqrt()) / (2. * a);
-                    let t2 = (-b + d.sqrt()) / (2. * a);
-
-                    Some(Hit::new(self, vec![t1, t2]))
-                } else {
-                    None
-                }
-            }
+/// The unit sphere centered on the origin.
+#[derive(Debug)]
+pub struct Sphere;
+
+impl Shape for Sphere {
+    fn local_intersect<'a>(&'a self, ray: &Ray, object: &'a Object) -> Intersections<'a> {
+        let sphere_to_ray = ray.origin - Tuple::point(0., 0., 0.);
+        let a = Tuple::dot(ray.direction, ray.direction);
+        let b = 2. * Tuple::dot(ray.direction, sphere_to_ray);
+        let c = Tuple::dot(sphere_to_ray, sphere_to_ray) - 1.;
+        let d = (b * b) - (4. * a * c);
+
+        if d >= 0. {
+            let t1 = (-b - d.sqrt()) / (2. * a);
+            let t2 = (-b + d.sqrt()) / (2. * a);
+
+            Intersections::new(vec![
+                Intersection::new(t1, object),
+                Intersection::new(t2, object),
+            ])
+        } else {
+            Intersections::default()
         }
     }
+    fn local_normal_at(&self, point: Tuple) -> Tuple {
+        point - Tuple::point(0., 0., 0.)
+    }
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(Tuple::point(-1., -1., -1.), Tuple::point(1., 1., 1.))
+    }
 }
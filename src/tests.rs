@@ -1,8 +1,45 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
-    use std::f64::consts::PI;
+    use std::{
+        cell::Cell,
+        f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2, FRAC_PI_3, FRAC_PI_4, FRAC_PI_6, PI, SQRT_2},
+        rc::Rc,
+        time::Instant,
+    };
 
-    use crate::{canvas::Canvas, color::Color, matrix::*, ray::Ray, shapes::Shapes, tuple::Tuple};
+    use crate::{
+        bounds::BoundingBox,
+        bvh::Bvh,
+        camera::Camera,
+        canvas::{Canvas, PpmError},
+        color::Color,
+        cone::Cone,
+        csg::{intersection_allowed, Csg, Operation},
+        cube::Cube,
+        cylinder::Cylinder,
+        group::Group,
+        intersection::{Intersection, Intersections, EPSILON},
+        light::{lighting, PointLight},
+        material::Material,
+        matrix::*,
+        noise::{Basis, Fractal, Noise},
+        obj::{ObjError, ObjFile},
+        pattern::{
+            Blend, Checkers, Gradient, Pattern, Perturbed, RadialGradient, Ring, Solid, Stripe,
+            Texture,
+        },
+        plane::Plane,
+        ray::Ray,
+        shapes::{Object, Shape, Sphere},
+        triangle::{SmoothTriangle, Triangle},
+        tuple::Tuple,
+        uv::{
+            cube_map, cylindrical_map, planar_map, spherical_map, CubeFace, CubeMap, Filter,
+            ImageTexture, TextureMap, UvCheckers, UvMapping, UvPattern, Wrap,
+        },
+        world::World,
+    };
     // Putting It Together
     #[test]
     #[ignore]
@@ -32,14 +69,14 @@ mod tests {
 
         let mut c = Canvas::new(canvas_pixels, canvas_pixels);
         let color = Color::new(1., 0., 0.);
-        let sphere = Shapes::sphere(Matrix4x4::_IDENTITY);
+        let sphere = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
         for y in 0..canvas_pixels {
             let world_y = half - pixel_size * y as f64;
             for x in 0..canvas_pixels {
                 let world_x = -half + pixel_size * x as f64;
                 let pos = Tuple::point(world_x, world_y, wall_z);
                 let r = Ray::new(origin, (pos - origin).normalize());
-                if let Some(_) = sphere.intersect(r) {
+                if sphere.intersect(r).hit().is_some() {
                     c.set_pixel(x, y, color);
                 }
             }
@@ -58,14 +95,17 @@ mod tests {
 
         let mut c = Canvas::new(canvas_pixels, canvas_pixels);
         let color = Color::new(1., 0., 0.);
-        let sphere = Shapes::sphere(Matrix4x4::scaling(1., 0.5, 1.).inverse());
+        let sphere = Object::sphere(
+            Matrix4x4::scaling(1., 0.5, 1.).inverse(),
+            Material::default(),
+        );
         for y in 0..canvas_pixels {
             let world_y = half - pixel_size * y as f64;
             for x in 0..canvas_pixels {
                 let world_x = -half + pixel_size * x as f64;
                 let pos = Tuple::point(world_x, world_y, wall_z);
                 let r = Ray::new(origin, (pos - origin).normalize());
-                if let Some(_) = sphere.intersect(r) {
+                if sphere.intersect(r).hit().is_some() {
                     c.set_pixel(x, y, color);
                 }
             }
@@ -84,14 +124,17 @@ mod tests {
 
         let mut c = Canvas::new(canvas_pixels, canvas_pixels);
         let color = Color::new(1., 0., 0.);
-        let sphere = Shapes::sphere(Matrix4x4::scaling(0.5, 1., 1.).inverse());
+        let sphere = Object::sphere(
+            Matrix4x4::scaling(0.5, 1., 1.).inverse(),
+            Material::default(),
+        );
         for y in 0..canvas_pixels {
             let world_y = half - pixel_size * y as f64;
             for x in 0..canvas_pixels {
                 let world_x = -half + pixel_size * x as f64;
                 let pos = Tuple::point(world_x, world_y, wall_z);
                 let r = Ray::new(origin, (pos - origin).normalize());
-                if let Some(_) = sphere.intersect(r) {
+                if sphere.intersect(r).hit().is_some() {
                     c.set_pixel(x, y, color);
                 }
             }
@@ -110,14 +153,17 @@ mod tests {
 
         let mut c = Canvas::new(canvas_pixels, canvas_pixels);
         let color = Color::new(1., 0., 0.);
-        let sphere = Shapes::sphere((Matrix4x4::rotation_z(PI/4.) * Matrix4x4::scaling(0.5, 1., 1.)).inverse());
+        let sphere = Object::sphere(
+            (Matrix4x4::rotation_z(PI / 4.) * Matrix4x4::scaling(0.5, 1., 1.)).inverse(),
+            Material::default(),
+        );
         for y in 0..canvas_pixels {
             let world_y = half - pixel_size * y as f64;
             for x in 0..canvas_pixels {
                 let world_x = -half + pixel_size * x as f64;
                 let pos = Tuple::point(world_x, world_y, wall_z);
                 let r = Ray::new(origin, (pos - origin).normalize());
-                if let Some(_) = sphere.intersect(r) {
+                if sphere.intersect(r).hit().is_some() {
                     c.set_pixel(x, y, color);
                 }
             }
@@ -136,20 +182,803 @@ mod tests {
 
         let mut c = Canvas::new(canvas_pixels, canvas_pixels);
         let color = Color::new(1., 0., 0.);
-        let sphere = Shapes::sphere((Matrix4x4::rotation_z(PI/4.) * Matrix4x4::shearing(1., 0., 0., 0., 0., 0.)).inverse());
+        let sphere = Object::sphere(
+            (Matrix4x4::rotation_z(PI / 4.) * Matrix4x4::shearing(1., 0., 0., 0., 0., 0.))
+                .inverse(),
+            Material::default(),
+        );
         for y in 0..canvas_pixels {
             let world_y = half - pixel_size * y as f64;
             for x in 0..canvas_pixels {
                 let world_x = -half + pixel_size * x as f64;
                 let pos = Tuple::point(world_x, world_y, wall_z);
                 let r = Ray::new(origin, (pos - origin).normalize());
-                if let Some(_) = sphere.intersect(r) {
+                if sphere.intersect(r).hit().is_some() {
                     c.set_pixel(x, y, color);
                 }
             }
         }
         c.write_ppm("images/shrunk_and_skewed_circle.ppm");
     }
+    #[test]
+    #[ignore]
+    fn shaded_sphere() {    // Chapter 06
+        let origin = Tuple::point(0., 0., -5.);
+        let wall_z = 10.;
+        let wall_size = 7.;
+        let canvas_pixels = 100;
+        let pixel_size = wall_size / canvas_pixels as f64;
+        let half = wall_size / 2.;
+
+        let mut c = Canvas::new(canvas_pixels, canvas_pixels);
+        let material = Material {
+            color: Color::new(1., 0.2, 1.),
+            ..Material::default()
+        };
+        let sphere = Object::sphere(Matrix4x4::_IDENTITY, material);
+        let light = PointLight::new(Tuple::point(-10., 10., -10.), Color::new(1., 1., 1.));
+        for y in 0..canvas_pixels {
+            let world_y = half - pixel_size * y as f64;
+            for x in 0..canvas_pixels {
+                let world_x = -half + pixel_size * x as f64;
+                let pos = Tuple::point(world_x, world_y, wall_z);
+                let r = Ray::new(origin, (pos - origin).normalize());
+                if let Some(hit) = sphere.intersect(r).hit() {
+                    let point = r.position_at(hit.t());
+                    let normal = sphere.normal_at(point);
+                    let eye = -r.direction;
+                    let color = lighting(
+                        sphere.material(),
+                        &sphere,
+                        &light,
+                        point,
+                        eye,
+                        normal,
+                        false,
+                    );
+                    c.set_pixel(x, y, color);
+                }
+            }
+        }
+        c.write_ppm("images/shaded_sphere.ppm");
+    }
+    #[test]
+    #[ignore]
+    fn spheres_scene() {    // Chapter 07
+        let wall = Material {
+            color: Color::new(1., 0.9, 0.9),
+            specular: 0.,
+            ..Material::default()
+        };
+        let floor = Object::sphere(Matrix4x4::scaling(10., 0.01, 10.).inverse(), wall.clone());
+        let left_wall = Object::sphere(
+            (Matrix4x4::translation(0., 0., 5.)
+                * Matrix4x4::rotation_y(-FRAC_PI_4)
+                * Matrix4x4::rotation_x(FRAC_PI_2)
+                * Matrix4x4::scaling(10., 0.01, 10.))
+            .inverse(),
+            wall.clone(),
+        );
+        let right_wall = Object::sphere(
+            (Matrix4x4::translation(0., 0., 5.)
+                * Matrix4x4::rotation_y(FRAC_PI_4)
+                * Matrix4x4::rotation_x(FRAC_PI_2)
+                * Matrix4x4::scaling(10., 0.01, 10.))
+            .inverse(),
+            wall,
+        );
+        let middle = Object::sphere(
+            Matrix4x4::translation(-0.5, 1., 0.5).inverse(),
+            Material {
+                color: Color::new(0.1, 1., 0.5),
+                diffuse: 0.7,
+                specular: 0.3,
+                ..Material::default()
+            },
+        );
+        let right = Object::sphere(
+            (Matrix4x4::translation(1.5, 0.5, -0.5) * Matrix4x4::scaling(0.5, 0.5, 0.5)).inverse(),
+            Material {
+                color: Color::new(0.5, 1., 0.1),
+                diffuse: 0.7,
+                specular: 0.3,
+                ..Material::default()
+            },
+        );
+        let left = Object::sphere(
+            (Matrix4x4::translation(-1.5, 0.33, -0.75) * Matrix4x4::scaling(0.33, 0.33, 0.33))
+                .inverse(),
+            Material {
+                color: Color::new(1., 0.8, 0.1),
+                diffuse: 0.7,
+                specular: 0.3,
+                ..Material::default()
+            },
+        );
+        let world = World::new(
+            vec![floor, left_wall, right_wall, middle, right, left],
+            vec![PointLight::new(
+                Tuple::point(-10., 10., -10.),
+                Color::new(1., 1., 1.),
+            )],
+        );
+        let camera = Camera::new(
+            200,
+            100,
+            PI / 3.,
+            Matrix4x4::view_transform(
+                Tuple::point(0., 1.5, -5.),
+                Tuple::point(0., 1., 0.),
+                Tuple::vector(0., 1., 0.),
+            ),
+        );
+        camera.render(&world).write_ppm("images/spheres_scene.ppm");
+    }
+    #[test]
+    #[ignore]
+    fn plane_scene() {    // Chapter 09
+        let floor = Object::new(
+            Plane,
+            Matrix4x4::_IDENTITY,
+            Material {
+                color: Color::new(1., 0.9, 0.9),
+                specular: 0.,
+                ..Material::default()
+            },
+        );
+        let back_wall = Object::new(
+            Plane,
+            (Matrix4x4::translation(0., 0., 5.) * Matrix4x4::rotation_x(FRAC_PI_2)).inverse(),
+            Material {
+                color: Color::new(0.9, 0.9, 1.),
+                specular: 0.,
+                ..Material::default()
+            },
+        );
+        let middle = Object::sphere(
+            Matrix4x4::translation(-0.5, 1., 0.5).inverse(),
+            Material {
+                color: Color::new(0.1, 1., 0.5),
+                diffuse: 0.7,
+                specular: 0.3,
+                ..Material::default()
+            },
+        );
+        let right = Object::sphere(
+            (Matrix4x4::translation(1.5, 0.5, -0.5) * Matrix4x4::scaling(0.5, 0.5, 0.5)).inverse(),
+            Material {
+                color: Color::new(0.5, 1., 0.1),
+                diffuse: 0.7,
+                specular: 0.3,
+                ..Material::default()
+            },
+        );
+        let world = World::new(
+            vec![floor, back_wall, middle, right],
+            vec![PointLight::new(
+                Tuple::point(-10., 10., -10.),
+                Color::new(1., 1., 1.),
+            )],
+        );
+        let camera = Camera::new(
+            200,
+            100,
+            PI / 3.,
+            Matrix4x4::view_transform(
+                Tuple::point(0., 1.5, -5.),
+                Tuple::point(0., 1., 0.),
+                Tuple::vector(0., 1., 0.),
+            ),
+        );
+        camera.render(&world).write_ppm("images/plane_scene.ppm");
+    }
+    #[test]
+    #[ignore]
+    fn patterns() {    // Chapter 10
+        let floor = Object::new(
+            Plane,
+            Matrix4x4::_IDENTITY,
+            Material {
+                pattern: Some(Texture::new(
+                    Checkers::new(Color::new(0.9, 0.9, 0.9), Color::new(0.2, 0.2, 0.25)),
+                    Matrix4x4::rotation_y(FRAC_PI_6),
+                )),
+                specular: 0.,
+                ..Material::default()
+            },
+        );
+        let back_wall = Object::new(
+            Plane,
+            (Matrix4x4::translation(0., 0., 5.) * Matrix4x4::rotation_x(FRAC_PI_2)).inverse(),
+            Material {
+                pattern: Some(Texture::new(
+                    Ring::new(Color::new(0.9, 0.9, 1.), Color::new(0.6, 0.6, 0.8)),
+                    Matrix4x4::scaling(0.5, 0.5, 0.5).inverse(),
+                )),
+                specular: 0.,
+                ..Material::default()
+            },
+        );
+        let middle = Object::sphere(
+            Matrix4x4::translation(-0.5, 1., 0.5).inverse(),
+            Material {
+                pattern: Some(Texture::new(
+                    Stripe::new(Color::new(0.1, 1., 0.5), Color::new(0.1, 0.5, 1.)),
+                    (Matrix4x4::rotation_z(FRAC_PI_4) * Matrix4x4::scaling(0.2, 0.2, 0.2))
+                        .inverse(),
+                )),
+                diffuse: 0.7,
+                specular: 0.3,
+                ..Material::default()
+            },
+        );
+        let right = Object::sphere(
+            (Matrix4x4::translation(1.5, 0.5, -0.5) * Matrix4x4::scaling(0.5, 0.5, 0.5)).inverse(),
+            Material {
+                pattern: Some(Texture::new(
+                    Gradient::new(Color::new(1., 0.2, 0.1), Color::new(1., 0.9, 0.1)),
+                    (Matrix4x4::translation(-1., 0., 0.) * Matrix4x4::scaling(2., 1., 1.))
+                        .inverse(),
+                )),
+                diffuse: 0.7,
+                specular: 0.3,
+                ..Material::default()
+            },
+        );
+        let world = World::new(
+            vec![floor, back_wall, middle, right],
+            vec![PointLight::new(
+                Tuple::point(-10., 10., -10.),
+                Color::new(1., 1., 1.),
+            )],
+        );
+        let camera = Camera::new(
+            200,
+            100,
+            PI / 3.,
+            Matrix4x4::view_transform(
+                Tuple::point(0., 1.5, -5.),
+                Tuple::point(0., 1., 0.),
+                Tuple::vector(0., 1., 0.),
+            ),
+        );
+        camera.render(&world).write_ppm("images/patterns.ppm");
+    }
+    #[test]
+    #[ignore]
+    fn composed_patterns() {    // Chapter 10
+        let stripes = |a: Color, b: Color, transform: Matrix4x4| {
+            Texture::new(Stripe::new(a, b), transform)
+        };
+        let floor = Object::new(
+            Plane,
+            Matrix4x4::_IDENTITY,
+            Material {
+                pattern: Some(Texture::new(
+                    Checkers::new(
+                        stripes(
+                            Color::new(0.9, 0.9, 0.9),
+                            Color::new(0.7, 0.7, 0.7),
+                            Matrix4x4::scaling(0.25, 0.25, 0.25).inverse(),
+                        ),
+                        stripes(
+                            Color::new(0.3, 0.3, 0.35),
+                            Color::new(0.15, 0.15, 0.2),
+                            (Matrix4x4::rotation_y(FRAC_PI_2)
+                                * Matrix4x4::scaling(0.25, 0.25, 0.25))
+                            .inverse(),
+                        ),
+                    ),
+                    Matrix4x4::_IDENTITY,
+                )),
+                specular: 0.,
+                ..Material::default()
+            },
+        );
+        let back_wall = Object::new(
+            Plane,
+            (Matrix4x4::translation(0., 0., 5.) * Matrix4x4::rotation_x(FRAC_PI_2)).inverse(),
+            Material {
+                pattern: Some(Texture::new(
+                    RadialGradient::new(Color::new(0.9, 0.9, 1.), Color::new(0.5, 0.5, 0.8)),
+                    Matrix4x4::_IDENTITY,
+                )),
+                specular: 0.,
+                ..Material::default()
+            },
+        );
+        let marble = Object::sphere(
+            Matrix4x4::translation(-0.5, 1., 0.5).inverse(),
+            Material {
+                pattern: Some(Texture::new(
+                    Perturbed::new(
+                        stripes(
+                            Color::new(0.95, 0.95, 0.9),
+                            Color::new(0.3, 0.35, 0.4),
+                            (Matrix4x4::rotation_z(FRAC_PI_4)
+                                * Matrix4x4::scaling(0.15, 0.15, 0.15))
+                            .inverse(),
+                        ),
+                        0.3,
+                    ),
+                    Matrix4x4::_IDENTITY,
+                )),
+                specular: 0.6,
+                ..Material::default()
+            },
+        );
+        let plaid = Object::sphere(
+            (Matrix4x4::translation(1.5, 0.5, -0.5) * Matrix4x4::scaling(0.5, 0.5, 0.5)).inverse(),
+            Material {
+                pattern: Some(Texture::new(
+                    Blend::new(
+                        stripes(
+                            Color::new(1., 0.2, 0.1),
+                            Color::new(1., 1., 1.),
+                            Matrix4x4::scaling(0.2, 0.2, 0.2).inverse(),
+                        ),
+                        stripes(
+                            Color::new(0.1, 0.6, 0.2),
+                            Color::new(1., 1., 1.),
+                            (Matrix4x4::rotation_y(FRAC_PI_2) * Matrix4x4::scaling(0.2, 0.2, 0.2))
+                                .inverse(),
+                        ),
+                    ),
+                    Matrix4x4::_IDENTITY,
+                )),
+                diffuse: 0.7,
+                specular: 0.3,
+                ..Material::default()
+            },
+        );
+        let world = World::new(
+            vec![floor, back_wall, marble, plaid],
+            vec![PointLight::new(
+                Tuple::point(-10., 10., -10.),
+                Color::new(1., 1., 1.),
+            )],
+        );
+        let camera = Camera::new(
+            200,
+            100,
+            PI / 3.,
+            Matrix4x4::view_transform(
+                Tuple::point(0., 1.5, -5.),
+                Tuple::point(0., 1., 0.),
+                Tuple::vector(0., 1., 0.),
+            ),
+        );
+        camera
+            .render(&world)
+            .write_ppm("images/composed_patterns.ppm");
+    }
+    #[test]
+    #[ignore]
+    fn reflections() {    // Chapter 11
+        let floor = Object::new(
+            Plane,
+            Matrix4x4::_IDENTITY,
+            Material {
+                color: Color::new(0.3, 0.3, 0.35),
+                specular: 0.,
+                reflective: 0.4,
+                ..Material::default()
+            },
+        );
+        let back_wall = Object::new(
+            Plane,
+            (Matrix4x4::translation(0., 0., 5.) * Matrix4x4::rotation_x(FRAC_PI_2)).inverse(),
+            Material {
+                color: Color::new(0.9, 0.9, 1.),
+                specular: 0.,
+                ..Material::default()
+            },
+        );
+        let chrome = Object::sphere(
+            Matrix4x4::translation(-0.5, 1., 0.5).inverse(),
+            Material {
+                color: Color::new(0.1, 0.1, 0.1),
+                diffuse: 0.1,
+                specular: 1.,
+                shininess: 300.,
+                reflective: 0.9,
+                ..Material::default()
+            },
+        );
+        let right = Object::sphere(
+            (Matrix4x4::translation(1.5, 0.5, -0.5) * Matrix4x4::scaling(0.5, 0.5, 0.5)).inverse(),
+            Material {
+                color: Color::new(0.5, 1., 0.1),
+                diffuse: 0.7,
+                specular: 0.3,
+                ..Material::default()
+            },
+        );
+        let left = Object::sphere(
+            (Matrix4x4::translation(-1.8, 0.33, -0.75) * Matrix4x4::scaling(0.33, 0.33, 0.33))
+                .inverse(),
+            Material {
+                color: Color::new(1., 0.3, 0.1),
+                diffuse: 0.7,
+                specular: 0.3,
+                ..Material::default()
+            },
+        );
+        let world = World::new(
+            vec![floor, back_wall, chrome, right, left],
+            vec![PointLight::new(
+                Tuple::point(-10., 10., -10.),
+                Color::new(1., 1., 1.),
+            )],
+        );
+        let camera = Camera::new(
+            200,
+            100,
+            PI / 3.,
+            Matrix4x4::view_transform(
+                Tuple::point(0., 1.5, -5.),
+                Tuple::point(0., 1., 0.),
+                Tuple::vector(0., 1., 0.),
+            ),
+        );
+        camera.render(&world).write_ppm("images/reflections.ppm");
+    }
+    #[test]
+    #[ignore]
+    fn refraction() {    // Chapter 11
+        let floor = Object::new(
+            Plane,
+            Matrix4x4::_IDENTITY,
+            Material {
+                color: Color::new(0.35, 0.35, 0.4),
+                specular: 0.,
+                reflective: 0.2,
+                ..Material::default()
+            },
+        );
+        let back_wall = Object::new(
+            Plane,
+            (Matrix4x4::translation(0., 0., 5.) * Matrix4x4::rotation_x(FRAC_PI_2)).inverse(),
+            Material {
+                color: Color::new(0.9, 0.9, 1.),
+                specular: 0.,
+                ..Material::default()
+            },
+        );
+        let glass = Material {
+            color: Color::new(0.05, 0.05, 0.05),
+            diffuse: 0.1,
+            specular: 1.,
+            shininess: 300.,
+            reflective: 0.9,
+            transparency: 0.9,
+            refractive_index: 1.5,
+            ..Material::default()
+        };
+        let glass_ball = Object::sphere(
+            Matrix4x4::translation(-0.5, 1., 0.5).inverse(),
+            glass.clone(),
+        );
+        let air_bubble = Object::sphere(
+            (Matrix4x4::translation(-0.5, 1., 0.5) * Matrix4x4::scaling(0.5, 0.5, 0.5)).inverse(),
+            Material {
+                refractive_index: 1.0000034,
+                ..glass.clone()
+            },
+        );
+        let behind = Object::sphere(
+            (Matrix4x4::translation(-0.2, 0.5, 3.) * Matrix4x4::scaling(0.5, 0.5, 0.5)).inverse(),
+            Material {
+                color: Color::new(0.5, 1., 0.1),
+                diffuse: 0.7,
+                specular: 0.3,
+                ..Material::default()
+            },
+        );
+        let water = Object::new(
+            Cube,
+            (Matrix4x4::translation(1.8, 0.4, -0.6)
+                * Matrix4x4::rotation_y(FRAC_PI_6)
+                * Matrix4x4::scaling(0.4, 0.4, 0.4))
+            .inverse(),
+            Material {
+                color: Color::new(0., 0.05, 0.1),
+                reflective: 0.5,
+                refractive_index: 1.333,
+                ..glass
+            },
+        );
+        let world = World::new(
+            vec![floor, back_wall, glass_ball, air_bubble, behind, water],
+            vec![PointLight::new(
+                Tuple::point(-10., 10., -10.),
+                Color::new(1., 1., 1.),
+            )],
+        );
+        let camera = Camera::new(
+            200,
+            100,
+            PI / 3.,
+            Matrix4x4::view_transform(
+                Tuple::point(0., 1.5, -5.),
+                Tuple::point(0., 1., 0.),
+                Tuple::vector(0., 1., 0.),
+            ),
+        );
+        camera.render(&world).write_ppm("images/refraction.ppm");
+    }
+    #[test]
+    #[ignore]
+    fn hexagon() {    // Chapter 14
+        let hexagon_side = |n: f64| {
+            let corner = Object::sphere(
+                (Matrix4x4::translation(0., 0., -1.) * Matrix4x4::scaling(0.25, 0.25, 0.25))
+                    .inverse(),
+                Material::default(),
+            );
+            let edge = Object::new(
+                Cylinder::new(0., 1., false),
+                (Matrix4x4::translation(0., 0., -1.)
+                    * Matrix4x4::rotation_y(-FRAC_PI_6)
+                    * Matrix4x4::rotation_z(-FRAC_PI_2)
+                    * Matrix4x4::scaling(0.25, 1., 0.25))
+                .inverse(),
+                Material::default(),
+            );
+            Object::new(
+                Group::new(vec![corner, edge]),
+                Matrix4x4::rotation_y(n * FRAC_PI_3).inverse(),
+                Material::default(),
+            )
+        };
+        let hexagon = Object::new(
+            Group::new((0..6).map(|n| hexagon_side(n as f64)).collect()),
+            (Matrix4x4::translation(0., 1., 0.) * Matrix4x4::rotation_x(-FRAC_PI_6)).inverse(),
+            Material::default(),
+        );
+        let world = World::new(
+            vec![hexagon],
+            vec![PointLight::new(
+                Tuple::point(-10., 10., -10.),
+                Color::new(1., 1., 1.),
+            )],
+        );
+        let camera = Camera::new(
+            200,
+            100,
+            PI / 3.,
+            Matrix4x4::view_transform(
+                Tuple::point(0., 2.5, -5.),
+                Tuple::point(0., 1., 0.),
+                Tuple::vector(0., 1., 0.),
+            ),
+        );
+        camera.render(&world).write_ppm("images/hexagon.ppm");
+    }
+    #[test]
+    #[ignore]
+    fn bvh_benchmark() {
+        // A sphere tessellated into 20,000 flat triangles, timed against the
+        // same triangles in a plain group. Fails if the hierarchy degrades
+        // or stops paying for itself.
+        let material = Material {
+            color: Color::new(1., 0.2, 0.3),
+            ..Material::default()
+        };
+        let tessellated = || {
+            let (rings, segments) = (100, 100);
+            let vertex = |ring: usize, segment: usize| {
+                let theta = PI * ring as f64 / rings as f64;
+                let phi = 2. * PI * segment as f64 / segments as f64;
+                Tuple::point(
+                    theta.sin() * phi.cos(),
+                    theta.cos(),
+                    theta.sin() * phi.sin(),
+                )
+            };
+            let mut triangles = Vec::new();
+            for ring in 0..rings {
+                for segment in 0..segments {
+                    let (a, b) = (vertex(ring, segment), vertex(ring, segment + 1));
+                    let (c, d) = (vertex(ring + 1, segment + 1), vertex(ring + 1, segment));
+                    for (p1, p2, p3) in [(a, b, c), (a, c, d)] {
+                        triangles.push(Object::new(
+                            Triangle::new(p1, p2, p3),
+                            Matrix4x4::_IDENTITY,
+                            material.clone(),
+                        ));
+                    }
+                }
+            }
+            triangles
+        };
+        let bvh = Bvh::new(tessellated());
+        let stats = bvh.stats();
+        // A balanced split of 20,000 triangles into leaves of at most four
+        // is about 13 levels deep.
+        assert!(stats.depth <= 24, "{:?}", stats);
+        assert!((1. ..=4.).contains(&stats.average_leaf_size), "{:?}", stats);
+        let light = PointLight::new(Tuple::point(-10., 10., -10.), Color::new(1., 1., 1.));
+        let camera = Camera::new(
+            100,
+            50,
+            PI / 3.,
+            Matrix4x4::view_transform(
+                Tuple::point(0., 1.5, -5.),
+                Tuple::point(0., 0.5, 0.),
+                Tuple::vector(0., 1., 0.),
+            ),
+        );
+        let floor = || {
+            Object::new(
+                Plane,
+                Matrix4x4::translation(0., -1., 0.).inverse(),
+                Material::default(),
+            )
+        };
+        let world = World::new(
+            vec![
+                floor(),
+                Object::new(bvh, Matrix4x4::_IDENTITY, Material::default()),
+            ],
+            vec![light],
+        );
+        let start = Instant::now();
+        let image = camera.render(&world);
+        let bvh_time = start.elapsed();
+        image.write_ppm("images/bvh_benchmark.ppm");
+        let world = World::new(
+            vec![
+                floor(),
+                Object::new(
+                    Group::new(tessellated()),
+                    Matrix4x4::_IDENTITY,
+                    Material::default(),
+                ),
+            ],
+            vec![light],
+        );
+        let start = Instant::now();
+        camera.render(&world);
+        let group_time = start.elapsed();
+        assert!(
+            bvh_time * 10 < group_time,
+            "bvh {:?}, group {:?}",
+            bvh_time,
+            group_time
+        );
+    }
+    #[test]
+    #[ignore]
+    fn fractal_noise() {
+        // Perlin fBm, simplex fBm, turbulence and ridged noise, left to right.
+        let noise = Noise::new(1);
+        let perlin = Fractal::default();
+        let simplex = Fractal {
+            basis: Basis::Simplex,
+            ..Fractal::default()
+        };
+        let size = 100;
+        let mut c = Canvas::new(size * 4, size);
+        for y in 0..size {
+            for x in 0..size {
+                let point = Tuple::point(x as f64 / 25., y as f64 / 25., 0.5);
+                let values = [
+                    (noise.fbm(point, &perlin) + 1.) / 2.,
+                    (noise.fbm(point, &simplex) + 1.) / 2.,
+                    noise.turbulence(point, &perlin),
+                    noise.ridged(point, &simplex),
+                ];
+                for (i, v) in values.iter().enumerate() {
+                    c.set_pixel(i * size + x, y, Color::new(*v, *v, *v));
+                }
+            }
+        }
+        c.write_ppm("images/fractal_noise.ppm");
+    }
+    #[test]
+    #[ignore]
+    fn uv_mapping() {
+        // A made-up planet map from fractal noise, wrapped around a sphere.
+        let noise = Noise::new(3);
+        let (width, height) = (256, 128);
+        let mut map = Canvas::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let theta = 2. * PI * x as f64 / width as f64;
+                let phi = PI * y as f64 / height as f64;
+                let point = Tuple::point(
+                    phi.sin() * theta.cos() * 2.,
+                    phi.cos() * 2.,
+                    phi.sin() * theta.sin() * 2.,
+                );
+                let n = noise.fbm(point, &Fractal::default());
+                let color = if phi.cos().abs() > 0.9 {
+                    Color::new(0.95, 0.95, 1.)
+                } else if n > 0.05 {
+                    Color::new(0.2 + n, 0.5, 0.15)
+                } else {
+                    Color::new(0.05, 0.15, 0.5 + n)
+                };
+                map.set_pixel(x, y, color);
+            }
+        }
+        let planet = Object::sphere(
+            (Matrix4x4::translation(-0.5, 1., 0.5) * Matrix4x4::rotation_y(0.5)).inverse(),
+            Material {
+                pattern: Some(Texture::new(
+                    TextureMap::new(
+                        UvMapping::Spherical,
+                        ImageTexture::new(map, Filter::Bilinear, Wrap::Repeat),
+                    ),
+                    Matrix4x4::_IDENTITY,
+                )),
+                specular: 0.2,
+                ..Material::default()
+            },
+        );
+        let floor = Object::new(
+            Plane,
+            Matrix4x4::_IDENTITY,
+            Material {
+                pattern: Some(Texture::new(
+                    TextureMap::new(
+                        UvMapping::Planar,
+                        UvCheckers::new(
+                            2.,
+                            2.,
+                            Color::new(0.8, 0.8, 0.8),
+                            Color::new(0.3, 0.3, 0.3),
+                        ),
+                    ),
+                    Matrix4x4::_IDENTITY,
+                )),
+                specular: 0.,
+                ..Material::default()
+            },
+        );
+        let face = |a: Color| -> Rc<dyn UvPattern> { Rc::new(UvCheckers::new(4., 4., a, WHITE)) };
+        let cube = Object::new(
+            Cube,
+            (Matrix4x4::translation(1.6, 0.5, -0.5)
+                * Matrix4x4::rotation_y(-FRAC_PI_6)
+                * Matrix4x4::rotation_x(-0.3)
+                * Matrix4x4::scaling(0.5, 0.5, 0.5))
+            .inverse(),
+            Material {
+                pattern: Some(Texture::new(
+                    CubeMap::new([
+                        face(RED),
+                        face(Color::new(1., 1., 0.)),
+                        face(Color::new(0., 0.7, 0.)),
+                        face(Color::new(0., 1., 1.)),
+                        face(BLUE),
+                        face(Color::new(1., 0.5, 0.)),
+                    ]),
+                    Matrix4x4::_IDENTITY,
+                )),
+                ..Material::default()
+            },
+        );
+        let world = World::new(
+            vec![floor, planet, cube],
+            vec![PointLight::new(
+                Tuple::point(-10., 10., -10.),
+                Color::new(1., 1., 1.),
+            )],
+        );
+        let camera = Camera::new(
+            200,
+            100,
+            PI / 3.,
+            Matrix4x4::view_transform(
+                Tuple::point(0., 1.5, -5.),
+                Tuple::point(0., 1., 0.),
+                Tuple::vector(0., 1., 0.),
+            ),
+        );
+        camera.render(&world).write_ppm("images/uv_mapping.ppm");
+    }
     // Linear Algebra tests.
     #[test]
     fn point_w() {
@@ -329,13 +1158,13 @@ mod tests {
             [1., 8., 5., 3.],
             [0., 0., 5., 8.],
         ]);
-        let aT = Matrix4x4::new([
+        let a_t = Matrix4x4::new([
             [0., 9., 1., 0.],
             [9., 8., 8., 0.],
             [3., 0., 5., 5.],
             [0., 8., 3., 8.],
         ]);
-        assert_eq!(a.transpose(), aT);
+        assert_eq!(a.transpose(), a_t);
     }
     #[test]
     fn transpose_identity_matrix() {
@@ -355,6 +1184,24 @@ mod tests {
         assert_eq!(a.determinant(), -196.);
     }
     #[test]
+    fn matrix_2x2_inverse() {
+        let a = Matrix2x2::new([[2., 1.], [0., 1.]]);
+        assert_eq!(a.inverse(), Matrix2x2::new([[0.5, -0.5], [0., 1.]]));
+        assert_eq!(a * a.inverse(), Matrix2x2::_IDENTITY);
+    }
+    #[test]
+    fn matrix_3x3_transpose_and_inverse() {
+        let a = Matrix3x3::new([[1., 2., 0.], [0., 1., 0.], [0., 3., 1.]]);
+        assert_eq!(
+            a.transpose(),
+            Matrix3x3::new([[1., 0., 0.], [2., 1., 3.], [0., 0., 1.]])
+        );
+        assert_eq!(
+            a.inverse(),
+            Matrix3x3::new([[1., -2., 0.], [0., 1., 0.], [0., -3., 1.]])
+        );
+    }
+    #[test]
     fn matrix_3x3_submatrix() {
         let a = Matrix3x3::new([[1., 5., 0.], [-3., 2., 7.], [0., 6., -3.]]);
         let sub = Matrix2x2::new([[-3., 2.], [0., 6.]]);
@@ -620,33 +1467,114 @@ mod tests {
         assert_eq!(r.position_at(2.5), Tuple::point(4.5, 3., 4.));
     }
     // Shape tests.
-   /*  #[test]
+    #[test]
     fn sphere_ray_intersection() {
         let r = Ray::new(Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.));
-        let s = Shapes::Sphere;
-        let hit = s.intersect(r);
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let hit = s.intersect(r).hit();
         assert_eq!(hit.unwrap().t(), 4.);
 
         let r = Ray::new(Tuple::point(0., 1., -5.), Tuple::vector(0., 0., 1.));
-        let s = Shapes::Sphere;
-        let hit = s.intersect(r);
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let hit = s.intersect(r).hit();
         assert_eq!(hit.unwrap().t(), 5.);
 
         let r = Ray::new(Tuple::point(0., 2., -5.), Tuple::vector(0., 0., 1.));
-        let s = Shapes::Sphere;
-        let hit = s.intersect(r);
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let hit = s.intersect(r).hit();
         assert_eq!(hit, None);
 
         let r = Ray::new(Tuple::point(0., 0., 0.), Tuple::vector(0., 0., 1.));
-        let s = Shapes::Sphere;
-        let hit = s.intersect(r);
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let hit = s.intersect(r).hit();
         assert_eq!(hit.unwrap().t(), 1.);
 
         let r = Ray::new(Tuple::point(0., 0., 5.), Tuple::vector(0., 0., 1.));
-        let s = Shapes::Sphere;
-        let hit = s.intersect(r);
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let hit = s.intersect(r).hit();
         assert_eq!(hit, None);
-    } */
+    }
+    #[test]
+    fn sphere_intersections_set_object() {
+        let r = Ray::new(Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.));
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let xs = s.intersect(r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].object(), &s);
+        assert_eq!(xs[1].object(), &s);
+    }
+    #[test]
+    fn tangent_sphere_intersection() {
+        let r = Ray::new(Tuple::point(0., 1., -5.), Tuple::vector(0., 0., 1.));
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let xs = s.intersect(r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t(), 5.);
+        assert_eq!(xs[1].t(), 5.);
+    }
+    // Hits.
+    #[test]
+    fn hit_all_positive() {
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let i1 = Intersection::new(1., &s);
+        let i2 = Intersection::new(2., &s);
+        let xs = Intersections::new(vec![i2, i1]);
+        assert_eq!(xs.hit(), Some(i1));
+    }
+    #[test]
+    fn hit_some_negative() {
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let i1 = Intersection::new(-1., &s);
+        let i2 = Intersection::new(1., &s);
+        let xs = Intersections::new(vec![i2, i1]);
+        assert_eq!(xs.hit(), Some(i2));
+    }
+    #[test]
+    fn hit_all_negative() {
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let i1 = Intersection::new(-2., &s);
+        let i2 = Intersection::new(-1., &s);
+        let xs = Intersections::new(vec![i2, i1]);
+        assert_eq!(xs.hit(), None);
+    }
+    #[test]
+    fn hit_lowest_nonnegative() {
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let i1 = Intersection::new(5., &s);
+        let i2 = Intersection::new(7., &s);
+        let i3 = Intersection::new(-3., &s);
+        let i4 = Intersection::new(2., &s);
+        let xs = Intersections::new(vec![i1, i2, i3, i4]);
+        assert_eq!(xs.hit(), Some(i4));
+    }
+    #[test]
+    fn merged_intersections_stay_sorted() {
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let mut xs = Intersections::new(vec![Intersection::new(3., &s), Intersection::new(1., &s)]);
+        xs.merge(Intersections::new(vec![
+            Intersection::new(2., &s),
+            Intersection::new(-1., &s),
+        ]));
+        let ts: Vec<f64> = xs.iter().map(|i| i.t()).collect();
+        assert_eq!(ts, vec![-1., 1., 2., 3.]);
+
+        xs.merge(Intersections::default());
+        assert_eq!(xs.len(), 4);
+        let mut empty = Intersections::default();
+        empty.merge(xs);
+        assert_eq!(empty.len(), 4);
+
+        // Ties keep the intersections that were already there first.
+        let other = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let mut xs = Intersections::new(vec![Intersection::new(2., &s)]);
+        xs.merge(Intersections::new(vec![
+            Intersection::new(2., &other),
+            Intersection::new(5., &other),
+        ]));
+        assert!(std::ptr::eq(xs[0].object(), &s));
+        assert!(std::ptr::eq(xs[1].object(), &other));
+        assert_eq!(xs[2].t(), 5.);
+    }
     // Ray transformations.
     #[test]
     fn ray_translation() {
@@ -667,52 +1595,3130 @@ mod tests {
     #[test]
     fn scaled_ray_sphere_intersection() {
         let r = Ray::new(Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.));
-        let s = Shapes::sphere(Matrix4x4::scaling(2., 2., 2.).inverse());
-        assert_eq!(s.intersect(r).unwrap().xs(), vec![3., 7.]);
+        let s = Object::sphere(
+            Matrix4x4::scaling(2., 2., 2.).inverse(),
+            Material::default(),
+        );
+        let xs = s.intersect(r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t(), 3.);
+        assert_eq!(xs[1].t(), 7.);
     }
     #[test]
     fn translated_ray_sphere_intersection() {
         let r = Ray::new(Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.));
-        let s = Shapes::sphere(Matrix4x4::translation(5., 0., 0.));
-        assert_eq!(s.intersect(r), None);
+        let s = Object::sphere(Matrix4x4::translation(5., 0., 0.), Material::default());
+        assert!(s.intersect(r).is_empty());
+    }
+    // Abstract shapes.
+    /// Records the object-space ray it was intersected with.
+    #[derive(Debug, Default)]
+    struct TestShape {
+        saved_ray: Rc<Cell<Option<Ray>>>,
+    }
+    impl Shape for TestShape {
+        fn local_intersect<'a>(&'a self, ray: &Ray, _object: &'a Object) -> Intersections<'a> {
+            self.saved_ray.set(Some(*ray));
+            Intersections::default()
+        }
+        fn local_normal_at(&self, point: Tuple) -> Tuple {
+            Tuple::vector(point.x, point.y, point.z)
+        }
+        fn bounds(&self) -> BoundingBox {
+            BoundingBox::new(Tuple::point(-1., -1., -1.), Tuple::point(1., 1., 1.))
+        }
     }
-    // Color tests.
     #[test]
-    fn add_colors() {
+    fn shape_transform_and_material() {
+        let m = Material {
+            ambient: 1.,
+            ..Material::default()
+        };
+        let mut s = Object::new(TestShape::default(), Matrix4x4::_IDENTITY, m.clone());
+        assert_eq!(s.transform(), Matrix4x4::_IDENTITY);
+        assert_eq!(*s.material(), m);
+        s.set_transform(Matrix4x4::translation(2., 3., 4.));
+        assert_eq!(s.transform(), Matrix4x4::translation(2., 3., 4.));
+    }
+    #[test]
+    fn intersect_scaled_shape() {
+        let r = Ray::new(Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.));
+        let shape = TestShape::default();
+        let saved_ray = shape.saved_ray.clone();
+        let s = Object::new(
+            shape,
+            Matrix4x4::scaling(2., 2., 2.).inverse(),
+            Material::default(),
+        );
+        s.intersect(r);
+        let local = saved_ray.get().unwrap();
+        assert_eq!(local.origin, Tuple::point(0., 0., -2.5));
+        assert_eq!(local.direction, Tuple::vector(0., 0., 0.5));
+    }
+    #[test]
+    fn intersect_translated_shape() {
+        let r = Ray::new(Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.));
+        let shape = TestShape::default();
+        let saved_ray = shape.saved_ray.clone();
+        let s = Object::new(
+            shape,
+            Matrix4x4::translation(5., 0., 0.).inverse(),
+            Material::default(),
+        );
+        s.intersect(r);
+        let local = saved_ray.get().unwrap();
+        assert_eq!(local.origin, Tuple::point(-5., 0., -5.));
+        assert_eq!(local.direction, Tuple::vector(0., 0., 1.));
+    }
+    #[test]
+    fn translated_shape_normal() {
+        let s = Object::new(
+            TestShape::default(),
+            Matrix4x4::translation(0., 1., 0.).inverse(),
+            Material::default(),
+        );
+        let n = s.normal_at(Tuple::point(0., 1. + FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+        assert_eq!(n, Tuple::vector(0., FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+    }
+    #[test]
+    fn transformed_shape_normal() {
+        let s = Object::new(
+            TestShape::default(),
+            (Matrix4x4::scaling(1., 0.5, 1.) * Matrix4x4::rotation_z(PI / 5.)).inverse(),
+            Material::default(),
+        );
+        let n = s.normal_at(Tuple::point(
+            0.,
+            2.0_f64.sqrt() / 2.,
+            -(2.0_f64.sqrt()) / 2.,
+        ));
+        assert_eq!(n, Tuple::vector(0., 0.97014, -0.24254));
+    }
+    // Planes.
+    #[test]
+    fn plane_normal_is_constant() {
+        let p = Plane;
         assert_eq!(
-            Color::new(0.9, 0.6, 0.75) + Color::new(0.7, 0.1, 0.25),
-            Color::new(1.6, 0.7, 1.0)
-        )
+            p.local_normal_at(Tuple::point(0., 0., 0.)),
+            Tuple::vector(0., 1., 0.)
+        );
+        assert_eq!(
+            p.local_normal_at(Tuple::point(10., 0., -10.)),
+            Tuple::vector(0., 1., 0.)
+        );
+        assert_eq!(
+            p.local_normal_at(Tuple::point(-5., 0., 150.)),
+            Tuple::vector(0., 1., 0.)
+        );
     }
     #[test]
-    fn sub_colors() {
+    fn intersect_plane_parallel_ray() {
+        let p = Object::new(Plane, Matrix4x4::_IDENTITY, Material::default());
+        let r = Ray::new(Tuple::point(0., 10., 0.), Tuple::vector(0., 0., 1.));
+        assert!(p.intersect(r).is_empty());
+    }
+    #[test]
+    fn intersect_plane_coplanar_ray() {
+        let p = Object::new(Plane, Matrix4x4::_IDENTITY, Material::default());
+        let r = Ray::new(Tuple::point(0., 0., 0.), Tuple::vector(0., 0., 1.));
+        assert!(p.intersect(r).is_empty());
+    }
+    #[test]
+    fn intersect_plane_from_above() {
+        let p = Object::new(Plane, Matrix4x4::_IDENTITY, Material::default());
+        let r = Ray::new(Tuple::point(0., 1., 0.), Tuple::vector(0., -1., 0.));
+        let xs = p.intersect(r);
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t(), 1.);
+        assert_eq!(xs[0].object(), &p);
+    }
+    #[test]
+    fn intersect_plane_from_below() {
+        let p = Object::new(Plane, Matrix4x4::_IDENTITY, Material::default());
+        let r = Ray::new(Tuple::point(0., -1., 0.), Tuple::vector(0., 1., 0.));
+        let xs = p.intersect(r);
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t(), 1.);
+    }
+    #[test]
+    fn rotated_plane_as_wall() {
+        let wall = Object::new(
+            Plane,
+            (Matrix4x4::translation(0., 0., 5.) * Matrix4x4::rotation_x(FRAC_PI_2)).inverse(),
+            Material::default(),
+        );
+        let r = Ray::new(Tuple::point(0., 1., 0.), Tuple::vector(0., 0., 1.));
+        let xs = wall.intersect(r);
+        assert_eq!(xs.len(), 1);
+        assert!((xs[0].t() - 5.).abs() < EPSILON);
         assert_eq!(
-            Color::new(0.9, 0.6, 0.75) - Color::new(0.7, 0.1, 0.25),
-            Color::new(0.2, 0.5, 0.5)
-        )
+            wall.normal_at(Tuple::point(0., 1., 5.)),
+            Tuple::vector(0., 0., 1.)
+        );
     }
     #[test]
-    fn scale_color() {
-        assert_eq!(Color::new(0.2, 0.3, 0.4) * 2.0, Color::new(0.4, 0.6, 0.8))
+    fn plane_in_world() {
+        let mut w = World::default_world();
+        w.objects.push(Object::new(
+            Plane,
+            Matrix4x4::translation(0., -1., 0.).inverse(),
+            Material::default(),
+        ));
+        let r = Ray::new(
+            Tuple::point(0., 0., -5.),
+            Tuple::vector(0., -0.05, 1.).normalize(),
+        );
+        let xs = w.intersect_world(&r);
+        assert_eq!(xs.len(), 5);
+        assert_eq!(xs[4].object(), &w.objects[2]);
     }
+    // Cubes.
     #[test]
-    fn mul_colors() {
+    fn ray_intersects_cube() {
+        let c = Object::new(Cube, Matrix4x4::_IDENTITY, Material::default());
+        let cases = [
+            (
+                Tuple::point(5., 0.5, 0.),
+                Tuple::vector(-1., 0., 0.),
+                4.,
+                6.,
+            ),
+            (
+                Tuple::point(-5., 0.5, 0.),
+                Tuple::vector(1., 0., 0.),
+                4.,
+                6.,
+            ),
+            (
+                Tuple::point(0.5, 5., 0.),
+                Tuple::vector(0., -1., 0.),
+                4.,
+                6.,
+            ),
+            (
+                Tuple::point(0.5, -5., 0.),
+                Tuple::vector(0., 1., 0.),
+                4.,
+                6.,
+            ),
+            (
+                Tuple::point(0.5, 0., 5.),
+                Tuple::vector(0., 0., -1.),
+                4.,
+                6.,
+            ),
+            (
+                Tuple::point(0.5, 0., -5.),
+                Tuple::vector(0., 0., 1.),
+                4.,
+                6.,
+            ),
+            (
+                Tuple::point(0., 0.5, 0.),
+                Tuple::vector(0., 0., 1.),
+                -1.,
+                1.,
+            ),
+        ];
+        for (origin, direction, t1, t2) in cases {
+            let xs = c.intersect(Ray::new(origin, direction));
+            assert_eq!(xs.len(), 2);
+            assert_eq!(xs[0].t(), t1);
+            assert_eq!(xs[1].t(), t2);
+        }
+    }
+    #[test]
+    fn ray_misses_cube() {
+        let c = Object::new(Cube, Matrix4x4::_IDENTITY, Material::default());
+        let cases = [
+            (
+                Tuple::point(-2., 0., 0.),
+                Tuple::vector(0.2673, 0.5345, 0.8018),
+            ),
+            (
+                Tuple::point(0., -2., 0.),
+                Tuple::vector(0.8018, 0.2673, 0.5345),
+            ),
+            (
+                Tuple::point(0., 0., -2.),
+                Tuple::vector(0.5345, 0.8018, 0.2673),
+            ),
+            (Tuple::point(2., 0., 2.), Tuple::vector(0., 0., -1.)),
+            (Tuple::point(0., 2., 2.), Tuple::vector(0., -1., 0.)),
+            (Tuple::point(2., 2., 0.), Tuple::vector(-1., 0., 0.)),
+        ];
+        for (origin, direction) in cases {
+            assert!(c.intersect(Ray::new(origin, direction)).is_empty());
+        }
+    }
+    #[test]
+    fn cube_normal() {
+        let c = Cube;
+        let cases = [
+            (Tuple::point(1., 0.5, -0.8), Tuple::vector(1., 0., 0.)),
+            (Tuple::point(-1., -0.2, 0.9), Tuple::vector(-1., 0., 0.)),
+            (Tuple::point(-0.4, 1., -0.1), Tuple::vector(0., 1., 0.)),
+            (Tuple::point(0.3, -1., -0.7), Tuple::vector(0., -1., 0.)),
+            (Tuple::point(-0.6, 0.3, 1.), Tuple::vector(0., 0., 1.)),
+            (Tuple::point(0.4, 0.4, -1.), Tuple::vector(0., 0., -1.)),
+            (Tuple::point(1., 1., 1.), Tuple::vector(1., 0., 0.)),
+            (Tuple::point(-1., -1., -1.), Tuple::vector(-1., 0., 0.)),
+        ];
+        for (point, normal) in cases {
+            assert_eq!(c.local_normal_at(point), normal);
+        }
+    }
+    #[test]
+    fn transformed_cube_as_box() {
+        let c = Object::new(
+            Cube,
+            (Matrix4x4::translation(0., 1., 0.) * Matrix4x4::scaling(2., 1., 0.5)).inverse(),
+            Material::default(),
+        );
+        let r = Ray::new(Tuple::point(-5., 1.5, 0.), Tuple::vector(1., 0., 0.));
+        let xs = c.intersect(r);
+        assert_eq!(xs.len(), 2);
+        assert!((xs[0].t() - 3.).abs() < EPSILON);
+        assert!((xs[1].t() - 7.).abs() < EPSILON);
         assert_eq!(
-            Color::new(1.0, 0.2, 0.4) * Color::new(0.9, 1.0, 0.1),
-            Color::new(0.9, 0.2, 0.04)
-        )
+            c.normal_at(Tuple::point(-2., 1.5, 0.)),
+            Tuple::vector(-1., 0., 0.)
+        );
+        assert_eq!(
+            c.normal_at(Tuple::point(0., 1.5, 0.5)),
+            Tuple::vector(0., 0., 1.)
+        );
     }
-    // Canvas tests.
+    // Cylinders.
     #[test]
-    fn set_pixel_canvas() {
-        let mut c = Canvas::new(10, 20);
-        let red = Color::new(1.0, 0.0, 0.0);
-        c.set_pixel(2, 3, red);
-        assert_eq!(c.pixel_at(2, 3), red);
+    fn ray_misses_cylinder() {
+        let cyl = Object::new(
+            Cylinder::default(),
+            Matrix4x4::_IDENTITY,
+            Material::default(),
+        );
+        let cases = [
+            (Tuple::point(1., 0., 0.), Tuple::vector(0., 1., 0.)),
+            (Tuple::point(0., 0., 0.), Tuple::vector(0., 1., 0.)),
+            (Tuple::point(0., 0., -5.), Tuple::vector(1., 1., 1.)),
+        ];
+        for (origin, direction) in cases {
+            assert!(cyl
+                .intersect(Ray::new(origin, direction.normalize()))
+                .is_empty());
+        }
     }
     #[test]
-    fn write_blank_canvas() {
-        let c = Canvas::new(10, 20);
-        c.write_ppm("images/blank.ppm");
+    fn ray_strikes_cylinder() {
+        let cyl = Object::new(
+            Cylinder::default(),
+            Matrix4x4::_IDENTITY,
+            Material::default(),
+        );
+        let cases = [
+            (Tuple::point(1., 0., -5.), Tuple::vector(0., 0., 1.), 5., 5.),
+            (Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.), 4., 6.),
+            (
+                Tuple::point(0.5, 0., -5.),
+                Tuple::vector(0.1, 1., 1.),
+                6.80798,
+                7.08872,
+            ),
+        ];
+        for (origin, direction, t0, t1) in cases {
+            let xs = cyl.intersect(Ray::new(origin, direction.normalize()));
+            assert_eq!(xs.len(), 2);
+            assert!((xs[0].t() - t0).abs() < EPSILON);
+            assert!((xs[1].t() - t1).abs() < EPSILON);
+        }
+    }
+    #[test]
+    fn cylinder_normal() {
+        let cyl = Cylinder::default();
+        let cases = [
+            (Tuple::point(1., 0., 0.), Tuple::vector(1., 0., 0.)),
+            (Tuple::point(0., 5., -1.), Tuple::vector(0., 0., -1.)),
+            (Tuple::point(0., -2., 1.), Tuple::vector(0., 0., 1.)),
+            (Tuple::point(-1., 1., 0.), Tuple::vector(-1., 0., 0.)),
+        ];
+        for (point, normal) in cases {
+            assert_eq!(cyl.local_normal_at(point), normal);
+        }
+    }
+    #[test]
+    fn default_cylinder_bounds() {
+        let cyl = Cylinder::default();
+        assert_eq!(cyl.minimum, f64::NEG_INFINITY);
+        assert_eq!(cyl.maximum, f64::INFINITY);
+        assert!(!cyl.closed);
+    }
+    #[test]
+    fn intersect_truncated_cylinder() {
+        let cyl = Object::new(
+            Cylinder::new(1., 2., false),
+            Matrix4x4::_IDENTITY,
+            Material::default(),
+        );
+        let cases = [
+            (Tuple::point(0., 1.5, 0.), Tuple::vector(0.1, 1., 0.), 0),
+            (Tuple::point(0., 3., -5.), Tuple::vector(0., 0., 1.), 0),
+            (Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.), 0),
+            (Tuple::point(0., 2., -5.), Tuple::vector(0., 0., 1.), 0),
+            (Tuple::point(0., 1., -5.), Tuple::vector(0., 0., 1.), 0),
+            (Tuple::point(0., 1.5, -2.), Tuple::vector(0., 0., 1.), 2),
+        ];
+        for (origin, direction, count) in cases {
+            assert_eq!(
+                cyl.intersect(Ray::new(origin, direction.normalize())).len(),
+                count
+            );
+        }
+    }
+    #[test]
+    fn intersect_capped_cylinder() {
+        let cyl = Object::new(
+            Cylinder::new(1., 2., true),
+            Matrix4x4::_IDENTITY,
+            Material::default(),
+        );
+        let cases = [
+            (Tuple::point(0., 3., 0.), Tuple::vector(0., -1., 0.), 2),
+            (Tuple::point(0., 3., -2.), Tuple::vector(0., -1., 2.), 2),
+            (Tuple::point(0., 4., -2.), Tuple::vector(0., -1., 1.), 2),
+            (Tuple::point(0., 0., -2.), Tuple::vector(0., 1., 2.), 2),
+            (Tuple::point(0., -1., -2.), Tuple::vector(0., 1., 1.), 2),
+        ];
+        for (origin, direction, count) in cases {
+            assert_eq!(
+                cyl.intersect(Ray::new(origin, direction.normalize())).len(),
+                count
+            );
+        }
+    }
+    #[test]
+    fn capped_cylinder_normal() {
+        let cyl = Cylinder::new(1., 2., true);
+        let cases = [
+            (Tuple::point(0., 1., 0.), Tuple::vector(0., -1., 0.)),
+            (Tuple::point(0.5, 1., 0.), Tuple::vector(0., -1., 0.)),
+            (Tuple::point(0., 1., 0.5), Tuple::vector(0., -1., 0.)),
+            (Tuple::point(0., 2., 0.), Tuple::vector(0., 1., 0.)),
+            (Tuple::point(0.5, 2., 0.), Tuple::vector(0., 1., 0.)),
+            (Tuple::point(0., 2., 0.5), Tuple::vector(0., 1., 0.)),
+        ];
+        for (point, normal) in cases {
+            assert_eq!(cyl.local_normal_at(point), normal);
+        }
+    }
+    // Cones.
+    #[test]
+    fn ray_intersects_cone() {
+        let shape = Object::new(Cone::default(), Matrix4x4::_IDENTITY, Material::default());
+        let cases = [
+            (Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.), 5., 5.),
+            (
+                Tuple::point(0., 0., -5.),
+                Tuple::vector(1., 1., 1.),
+                8.66025,
+                8.66025,
+            ),
+            (
+                Tuple::point(1., 1., -5.),
+                Tuple::vector(-0.5, -1., 1.),
+                4.55006,
+                49.44994,
+            ),
+        ];
+        for (origin, direction, t0, t1) in cases {
+            let xs = shape.intersect(Ray::new(origin, direction.normalize()));
+            assert_eq!(xs.len(), 2);
+            assert!((xs[0].t() - t0).abs() < 1e-4);
+            assert!((xs[1].t() - t1).abs() < 1e-4);
+        }
+    }
+    #[test]
+    fn ray_parallel_to_cone_half() {
+        let shape = Object::new(Cone::default(), Matrix4x4::_IDENTITY, Material::default());
+        let r = Ray::new(
+            Tuple::point(0., 0., -1.),
+            Tuple::vector(0., 1., 1.).normalize(),
+        );
+        let xs = shape.intersect(r);
+        assert_eq!(xs.len(), 1);
+        assert!((xs[0].t() - 0.35355).abs() < EPSILON);
+    }
+    #[test]
+    fn ray_through_cone_apex_parallel_to_surface() {
+        let shape = Object::new(Cone::default(), Matrix4x4::_IDENTITY, Material::default());
+        let r = Ray::new(
+            Tuple::point(0., 0., 0.),
+            Tuple::vector(0., 1., 1.).normalize(),
+        );
+        assert!(shape.intersect(r).is_empty());
+    }
+    #[test]
+    fn intersect_capped_cone() {
+        let shape = Object::new(
+            Cone::new(-0.5, 0.5, true),
+            Matrix4x4::_IDENTITY,
+            Material::default(),
+        );
+        let cases = [
+            (Tuple::point(0., 0., -5.), Tuple::vector(0., 1., 0.), 0),
+            (Tuple::point(0., 0., -0.25), Tuple::vector(0., 1., 1.), 2),
+            (Tuple::point(0., 0., -0.25), Tuple::vector(0., 1., 0.), 4),
+        ];
+        for (origin, direction, count) in cases {
+            assert_eq!(
+                shape
+                    .intersect(Ray::new(origin, direction.normalize()))
+                    .len(),
+                count
+            );
+        }
+    }
+    #[test]
+    fn cone_normal() {
+        let shape = Cone::default();
+        let cases = [
+            (Tuple::point(0., 0., 0.), Tuple::vector(0., 0., 0.)),
+            (
+                Tuple::point(1., 1., 1.),
+                Tuple::vector(1., -(2.0_f64.sqrt()), 1.),
+            ),
+            (Tuple::point(-1., -1., 0.), Tuple::vector(-1., 1., 0.)),
+        ];
+        for (point, normal) in cases {
+            assert_eq!(shape.local_normal_at(point), normal);
+        }
+    }
+    #[test]
+    fn capped_cone_normal() {
+        let shape = Cone::new(-1., 2., true);
+        assert_eq!(
+            shape.local_normal_at(Tuple::point(0.5, 2., 0.)),
+            Tuple::vector(0., 1., 0.)
+        );
+        assert_eq!(
+            shape.local_normal_at(Tuple::point(0., -1., 0.5)),
+            Tuple::vector(0., -1., 0.)
+        );
+    }
+    // Triangles.
+    fn test_triangle() -> Triangle {
+        Triangle::new(
+            Tuple::point(0., 1., 0.),
+            Tuple::point(-1., 0., 0.),
+            Tuple::point(1., 0., 0.),
+        )
+    }
+    fn test_smooth_triangle() -> SmoothTriangle {
+        SmoothTriangle::new(
+            Tuple::point(0., 1., 0.),
+            Tuple::point(-1., 0., 0.),
+            Tuple::point(1., 0., 0.),
+            Tuple::vector(0., 1., 0.),
+            Tuple::vector(-1., 0., 0.),
+            Tuple::vector(1., 0., 0.),
+        )
+    }
+    #[test]
+    fn construct_triangle() {
+        let t = test_triangle();
+        assert_eq!(t.p1, Tuple::point(0., 1., 0.));
+        assert_eq!(t.p2, Tuple::point(-1., 0., 0.));
+        assert_eq!(t.p3, Tuple::point(1., 0., 0.));
+        assert_eq!(t.e1, Tuple::vector(-1., -1., 0.));
+        assert_eq!(t.e2, Tuple::vector(1., -1., 0.));
+        assert_eq!(t.normal, Tuple::vector(0., 0., -1.));
+    }
+    #[test]
+    fn triangle_normal() {
+        let t = test_triangle();
+        assert_eq!(t.local_normal_at(Tuple::point(0., 0.5, 0.)), t.normal);
+        assert_eq!(t.local_normal_at(Tuple::point(-0.5, 0.75, 0.)), t.normal);
+        assert_eq!(t.local_normal_at(Tuple::point(0.5, 0.25, 0.)), t.normal);
+    }
+    #[test]
+    fn ray_misses_triangle() {
+        let t = Object::new(test_triangle(), Matrix4x4::_IDENTITY, Material::default());
+        let cases = [
+            // Parallel to the triangle.
+            (Tuple::point(0., -1., -2.), Tuple::vector(0., 1., 0.)),
+            // Past each of the three edges.
+            (Tuple::point(1., 1., -2.), Tuple::vector(0., 0., 1.)),
+            (Tuple::point(-1., 1., -2.), Tuple::vector(0., 0., 1.)),
+            (Tuple::point(0., -1., -2.), Tuple::vector(0., 0., 1.)),
+        ];
+        for (origin, direction) in cases {
+            assert!(t.intersect(Ray::new(origin, direction)).is_empty());
+        }
+    }
+    #[test]
+    fn ray_strikes_triangle() {
+        let t = Object::new(test_triangle(), Matrix4x4::_IDENTITY, Material::default());
+        let r = Ray::new(Tuple::point(0., 0.5, -2.), Tuple::vector(0., 0., 1.));
+        let xs = t.intersect(r);
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t(), 2.);
+    }
+    #[test]
+    fn ray_strikes_small_triangle() {
+        let t = Object::new(
+            Triangle::new(
+                Tuple::point(0., 0.002, 0.),
+                Tuple::point(-0.001, 0., 0.),
+                Tuple::point(0.001, 0., 0.),
+            ),
+            Matrix4x4::_IDENTITY,
+            Material::default(),
+        );
+        let r = Ray::new(Tuple::point(0., 0.0005, -2.), Tuple::vector(0., 0., 1.));
+        let xs = t.intersect(r);
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t(), 2.);
+    }
+    #[test]
+    fn construct_smooth_triangle() {
+        let t = test_smooth_triangle();
+        assert_eq!(t.p1, Tuple::point(0., 1., 0.));
+        assert_eq!(t.n1, Tuple::vector(0., 1., 0.));
+        assert_eq!(t.n2, Tuple::vector(-1., 0., 0.));
+        assert_eq!(t.n3, Tuple::vector(1., 0., 0.));
+    }
+    #[test]
+    fn intersection_with_uv() {
+        let s = Object::new(test_triangle(), Matrix4x4::_IDENTITY, Material::default());
+        let i = Intersection::with_uv(3.5, &s, 0.2, 0.4);
+        assert_eq!(i.u(), 0.2);
+        assert_eq!(i.v(), 0.4);
+    }
+    #[test]
+    fn smooth_triangle_intersection_stores_uv() {
+        let t = Object::new(
+            test_smooth_triangle(),
+            Matrix4x4::_IDENTITY,
+            Material::default(),
+        );
+        let r = Ray::new(Tuple::point(-0.2, 0.3, -2.), Tuple::vector(0., 0., 1.));
+        let xs = t.intersect(r);
+        assert!((xs[0].u() - 0.45).abs() < EPSILON);
+        assert!((xs[0].v() - 0.25).abs() < EPSILON);
+    }
+    #[test]
+    fn smooth_triangle_interpolates_normal() {
+        let t = Object::new(
+            test_smooth_triangle(),
+            Matrix4x4::_IDENTITY,
+            Material::default(),
+        );
+        let i = Intersection::with_uv(1., &t, 0.45, 0.25);
+        let n = t.normal_at_hit(Tuple::point(0., 0., 0.), &i);
+        assert_eq!(n, Tuple::vector(-0.5547, 0.83205, 0.));
+    }
+    #[test]
+    fn prepare_normal_on_smooth_triangle() {
+        let t = Object::new(
+            test_smooth_triangle(),
+            Matrix4x4::_IDENTITY,
+            Material::default(),
+        );
+        let i = Intersection::with_uv(1., &t, 0.45, 0.25);
+        let r = Ray::new(Tuple::point(-0.2, 0.3, -2.), Tuple::vector(0., 0., 1.));
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert_eq!(comps.normalv, Tuple::vector(-0.5547, 0.83205, 0.));
+    }
+    // OBJ files.
+    #[test]
+    fn obj_ignores_unrecognized_lines() {
+        let gibberish = "There was a young lady named Bright
+who traveled much faster than light.
+She set out one day
+in a relative way,
+and came back the previous night.";
+        let obj = ObjFile::parse(gibberish).unwrap();
+        assert_eq!(obj.ignored, 5);
+    }
+    #[test]
+    fn obj_vertex_records() {
+        let file = "v -1 1 0
+v -1.0000 0.5000 0.0000
+v 1 0 0
+v 1 1 0";
+        let obj = ObjFile::parse(file).unwrap();
+        assert_eq!(
+            obj.vertices,
+            vec![
+                Tuple::point(-1., 1., 0.),
+                Tuple::point(-1., 0.5, 0.),
+                Tuple::point(1., 0., 0.),
+                Tuple::point(1., 1., 0.),
+            ]
+        );
+    }
+    #[test]
+    fn obj_triangle_faces() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+
+f 1 2 3
+f 1 3 4";
+        let obj = ObjFile::parse(file).unwrap();
+        assert_eq!(obj.default_group.len(), 2);
+        let t1 = obj.default_group[0].shape_as::<Triangle>().unwrap();
+        let t2 = obj.default_group[1].shape_as::<Triangle>().unwrap();
+        assert_eq!(t1.p1, obj.vertices[0]);
+        assert_eq!(t1.p2, obj.vertices[1]);
+        assert_eq!(t1.p3, obj.vertices[2]);
+        assert_eq!(t2.p1, obj.vertices[0]);
+        assert_eq!(t2.p2, obj.vertices[2]);
+        assert_eq!(t2.p3, obj.vertices[3]);
+    }
+    #[test]
+    fn obj_triangulates_polygons() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+v 0 2 0
+
+f 1 2 3 4 5";
+        let obj = ObjFile::parse(file).unwrap();
+        assert_eq!(obj.default_group.len(), 3);
+        let t3 = obj.default_group[2].shape_as::<Triangle>().unwrap();
+        assert_eq!(t3.p1, obj.vertices[0]);
+        assert_eq!(t3.p2, obj.vertices[3]);
+        assert_eq!(t3.p3, obj.vertices[4]);
+    }
+    #[test]
+    fn obj_named_groups() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+g FirstGroup
+f 1 2 3
+g SecondGroup
+f 1 3 4";
+        let obj = ObjFile::parse(file).unwrap();
+        assert!(obj.default_group.is_empty());
+        assert_eq!(obj.groups.len(), 2);
+        assert_eq!(obj.groups[0].0, "FirstGroup");
+        assert_eq!(obj.groups[1].0, "SecondGroup");
+        let t2 = obj.groups[1].1[0].shape_as::<Triangle>().unwrap();
+        assert_eq!(t2.p2, obj.vertices[2]);
+        assert_eq!(obj.into_objects().len(), 2);
+    }
+    #[test]
+    fn obj_vertex_normal_records() {
+        let file = "vn 0 0 1
+vn 0.707 0 -0.707
+vn 1 2 3";
+        let obj = ObjFile::parse(file).unwrap();
+        assert_eq!(
+            obj.normals,
+            vec![
+                Tuple::vector(0., 0., 1.),
+                Tuple::vector(0.707, 0., -0.707),
+                Tuple::vector(1., 2., 3.),
+            ]
+        );
+    }
+    #[test]
+    fn obj_faces_with_normals() {
+        let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+
+vn -1 0 0
+vn 1 0 0
+vn 0 1 0
+
+f 1//3 2//1 3//2
+f 1/0/3 2/102/1 3/14/2";
+        let obj = ObjFile::parse(file).unwrap();
+        for object in &obj.default_group {
+            let t = object.shape_as::<SmoothTriangle>().unwrap();
+            assert_eq!(t.p1, obj.vertices[0]);
+            assert_eq!(t.p2, obj.vertices[1]);
+            assert_eq!(t.p3, obj.vertices[2]);
+            assert_eq!(t.n1, obj.normals[2]);
+            assert_eq!(t.n2, obj.normals[0]);
+            assert_eq!(t.n3, obj.normals[1]);
+        }
+    }
+    #[test]
+    fn obj_negative_indices() {
+        let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+f -3 -2 -1";
+        let obj = ObjFile::parse(file).unwrap();
+        let t = obj.default_group[0].shape_as::<Triangle>().unwrap();
+        assert_eq!(t.p1, obj.vertices[0]);
+        assert_eq!(t.p3, obj.vertices[2]);
+    }
+    #[test]
+    fn obj_errors_report_line_numbers() {
+        match ObjFile::parse("v 0 1 0\nv 1 zero 0") {
+            Err(ObjError::Parse { line, .. }) => assert_eq!(line, 2),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+        match ObjFile::parse("v 0 1 0\nv -1 0 0\nv 1 0 0\n\nf 1 2 4") {
+            Err(ObjError::Parse { line, message }) => {
+                assert_eq!(line, 5);
+                assert_eq!(message, "vertex index 4 is out of range");
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+        match ObjFile::parse("v 0 1 0\nv -1 0 0\nf 1 2") {
+            Err(ObjError::Parse { line, .. }) => assert_eq!(line, 3),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+        match ObjFile::read("missing.obj") {
+            Err(ObjError::Io(_)) => {}
+            other => panic!("expected an I/O error, got {:?}", other),
+        }
+    }
+    // Groups.
+    #[test]
+    fn new_group_is_empty() {
+        let g = Group::new(vec![]);
+        assert!(g.children().is_empty());
+    }
+    #[test]
+    fn add_child_to_group() {
+        let mut g = Object::new(
+            Group::new(vec![]),
+            Matrix4x4::translation(1., 0., 0.),
+            Material::default(),
+        );
+        let s = Object::new(
+            TestShape::default(),
+            Matrix4x4::_IDENTITY,
+            Material::default(),
+        );
+        g.shape_as_mut::<Group>().unwrap().add_child(s);
+        let children = g.shape_as::<Group>().unwrap().children();
+        assert_eq!(children.len(), 1);
+        assert_eq!(
+            children[0].world_to_object(Tuple::point(0., 0., 0.)),
+            Tuple::point(1., 0., 0.)
+        );
+    }
+    #[test]
+    fn intersect_empty_group() {
+        let g = Object::new(
+            Group::new(vec![]),
+            Matrix4x4::_IDENTITY,
+            Material::default(),
+        );
+        let r = Ray::new(Tuple::point(0., 0., 0.), Tuple::vector(0., 0., 1.));
+        assert!(g.intersect(r).is_empty());
+    }
+    #[test]
+    fn intersect_nonempty_group() {
+        let s1 = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let s2 = Object::sphere(
+            Matrix4x4::translation(0., 0., -3.).inverse(),
+            Material::default(),
+        );
+        let s3 = Object::sphere(
+            Matrix4x4::translation(5., 0., 0.).inverse(),
+            Material::default(),
+        );
+        let g = Object::new(
+            Group::new(vec![s1, s2, s3]),
+            Matrix4x4::_IDENTITY,
+            Material::default(),
+        );
+        let children = g.shape_as::<Group>().unwrap().children();
+        let r = Ray::new(Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.));
+        let xs = g.intersect(r);
+        assert_eq!(xs.len(), 4);
+        assert_eq!(xs[0].object(), &children[1]);
+        assert_eq!(xs[1].object(), &children[1]);
+        assert_eq!(xs[2].object(), &children[0]);
+        assert_eq!(xs[3].object(), &children[0]);
+    }
+    #[test]
+    fn intersect_transformed_group() {
+        let s = Object::sphere(
+            Matrix4x4::translation(5., 0., 0.).inverse(),
+            Material::default(),
+        );
+        let g = Object::new(
+            Group::new(vec![s]),
+            Matrix4x4::scaling(2., 2., 2.).inverse(),
+            Material::default(),
+        );
+        let r = Ray::new(Tuple::point(10., 0., -10.), Tuple::vector(0., 0., 1.));
+        assert_eq!(g.intersect(r).len(), 2);
+    }
+    /// A sphere translated inside a scaled group inside a rotated group.
+    fn nested_group() -> Object {
+        let s = Object::sphere(
+            Matrix4x4::translation(5., 0., 0.).inverse(),
+            Material::default(),
+        );
+        let g2 = Object::new(
+            Group::new(vec![s]),
+            Matrix4x4::scaling(1., 2., 3.).inverse(),
+            Material::default(),
+        );
+        Object::new(
+            Group::new(vec![g2]),
+            Matrix4x4::rotation_y(FRAC_PI_2).inverse(),
+            Material::default(),
+        )
+    }
+    fn innermost(g1: &Object) -> &Object {
+        let g2 = &g1.shape_as::<Group>().unwrap().children()[0];
+        &g2.shape_as::<Group>().unwrap().children()[0]
+    }
+    #[test]
+    fn group_world_to_object() {
+        let s = Object::sphere(
+            Matrix4x4::translation(5., 0., 0.).inverse(),
+            Material::default(),
+        );
+        let g2 = Object::new(
+            Group::new(vec![s]),
+            Matrix4x4::scaling(2., 2., 2.).inverse(),
+            Material::default(),
+        );
+        let g1 = Object::new(
+            Group::new(vec![g2]),
+            Matrix4x4::rotation_y(FRAC_PI_2).inverse(),
+            Material::default(),
+        );
+        let s = innermost(&g1);
+        assert_eq!(
+            s.world_to_object(Tuple::point(-2., 0., -10.)),
+            Tuple::point(0., 0., -1.)
+        );
+    }
+    #[test]
+    fn group_normal_to_world() {
+        let g1 = nested_group();
+        let s = innermost(&g1);
+        let v = 3.0_f64.sqrt() / 3.;
+        let n = s.normal_to_world(Tuple::vector(v, v, v));
+        assert_eq!(n, Tuple::vector(0.28571, 0.42857, -0.85714));
+    }
+    #[test]
+    fn group_child_normal() {
+        let g1 = nested_group();
+        let s = innermost(&g1);
+        let n = s.normal_at(Tuple::point(1.7321, 1.1547, -5.5774));
+        assert_eq!(n, Tuple::vector(0.28570, 0.42854, -0.85716));
+    }
+    #[test]
+    fn moving_group_moves_children() {
+        let mut g1 = nested_group();
+        g1.set_transform(Matrix4x4::_IDENTITY);
+        let s = innermost(&g1);
+        assert_eq!(
+            s.world_to_object(Tuple::point(5., 0., 0.)),
+            Tuple::point(0., 0., 0.)
+        );
+    }
+    #[test]
+    fn obj_file_into_group() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+f 1 2 3
+g FirstGroup
+f 1 2 3
+g SecondGroup
+f 1 3 4";
+        let g = ObjFile::parse(file)
+            .unwrap()
+            .into_group(Matrix4x4::_IDENTITY);
+        let children = g.shape_as::<Group>().unwrap().children();
+        assert_eq!(children.len(), 3);
+        assert!(children[0].shape_as::<Triangle>().is_some());
+        assert_eq!(children[2].shape_as::<Group>().unwrap().children().len(), 1);
+    }
+    // Constructive solid geometry.
+    #[test]
+    fn construct_csg() {
+        let c = Csg::new(
+            Operation::Union,
+            Object::sphere(Matrix4x4::_IDENTITY, Material::default()),
+            Object::new(Cube, Matrix4x4::_IDENTITY, Material::default()),
+        );
+        assert_eq!(c.operation(), Operation::Union);
+        assert!(c.left().shape_as::<Sphere>().is_some());
+        assert!(c.right().shape_as::<Cube>().is_some());
+    }
+    #[test]
+    fn csg_operation_rules() {
+        use Operation::*;
+        let cases = [
+            (Union, true, true, true, false),
+            (Union, true, true, false, true),
+            (Union, true, false, true, false),
+            (Union, true, false, false, true),
+            (Union, false, true, true, false),
+            (Union, false, true, false, false),
+            (Union, false, false, true, true),
+            (Union, false, false, false, true),
+            (Intersection, true, true, true, true),
+            (Intersection, true, true, false, false),
+            (Intersection, true, false, true, true),
+            (Intersection, true, false, false, false),
+            (Intersection, false, true, true, true),
+            (Intersection, false, true, false, true),
+            (Intersection, false, false, true, false),
+            (Intersection, false, false, false, false),
+            (Difference, true, true, true, false),
+            (Difference, true, true, false, true),
+            (Difference, true, false, true, false),
+            (Difference, true, false, false, true),
+            (Difference, false, true, true, true),
+            (Difference, false, true, false, true),
+            (Difference, false, false, true, false),
+            (Difference, false, false, false, false),
+        ];
+        for (op, lhit, inl, inr, result) in cases {
+            assert_eq!(intersection_allowed(op, lhit, inl, inr), result);
+        }
+    }
+    #[test]
+    fn filter_csg_intersections() {
+        for (op, x0, x1) in [
+            (Operation::Union, 0, 3),
+            (Operation::Intersection, 1, 2),
+            (Operation::Difference, 0, 1),
+        ] {
+            let c = Csg::new(
+                op,
+                Object::sphere(Matrix4x4::_IDENTITY, Material::default()),
+                Object::new(Cube, Matrix4x4::_IDENTITY, Material::default()),
+            );
+            let xs = Intersections::new(vec![
+                Intersection::new(1., c.left()),
+                Intersection::new(2., c.right()),
+                Intersection::new(3., c.left()),
+                Intersection::new(4., c.right()),
+            ]);
+            let result = c.filter_intersections(&xs);
+            assert_eq!(result.len(), 2);
+            assert_eq!(result[0], xs[x0]);
+            assert_eq!(result[1], xs[x1]);
+        }
+    }
+    #[test]
+    fn ray_misses_csg() {
+        let c = Object::new(
+            Csg::new(
+                Operation::Union,
+                Object::sphere(Matrix4x4::_IDENTITY, Material::default()),
+                Object::new(Cube, Matrix4x4::_IDENTITY, Material::default()),
+            ),
+            Matrix4x4::_IDENTITY,
+            Material::default(),
+        );
+        let r = Ray::new(Tuple::point(0., 2., -5.), Tuple::vector(0., 0., 1.));
+        assert!(c.intersect(r).is_empty());
+    }
+    #[test]
+    fn ray_hits_csg() {
+        let c = Object::new(
+            Csg::new(
+                Operation::Union,
+                Object::sphere(Matrix4x4::_IDENTITY, Material::default()),
+                Object::sphere(
+                    Matrix4x4::translation(0., 0., 0.5).inverse(),
+                    Material::default(),
+                ),
+            ),
+            Matrix4x4::_IDENTITY,
+            Material::default(),
+        );
+        let csg = c.shape_as::<Csg>().unwrap();
+        let r = Ray::new(Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.));
+        let xs = c.intersect(r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t(), 4.);
+        assert_eq!(xs[0].object(), csg.left());
+        assert_eq!(xs[1].t(), 6.5);
+        assert_eq!(xs[1].object(), csg.right());
+    }
+    #[test]
+    fn csg_includes_nested_operands() {
+        let inner = Object::new(
+            Group::new(vec![Object::sphere(
+                Matrix4x4::_IDENTITY,
+                Material::default(),
+            )]),
+            Matrix4x4::_IDENTITY,
+            Material::default(),
+        );
+        let c = Csg::new(
+            Operation::Difference,
+            inner,
+            Object::new(Cube, Matrix4x4::_IDENTITY, Material::default()),
+        );
+        let sphere = &c.left().shape_as::<Group>().unwrap().children()[0];
+        assert!(c.left().includes(sphere));
+        assert!(!c.right().includes(sphere));
+    }
+    #[test]
+    fn transformed_csg_moves_operands() {
+        let c = Object::new(
+            Csg::new(
+                Operation::Difference,
+                Object::new(Cube, Matrix4x4::_IDENTITY, Material::default()),
+                Object::sphere(
+                    Matrix4x4::scaling(1.2, 1.2, 1.2).inverse(),
+                    Material::default(),
+                ),
+            ),
+            Matrix4x4::translation(0., 5., 0.).inverse(),
+            Material::default(),
+        );
+        let r = Ray::new(Tuple::point(0., 5.9, -5.), Tuple::vector(0., 0., 1.));
+        let xs = c.intersect(r);
+        let ts: Vec<f64> = xs.iter().map(|i| i.t()).collect();
+        assert_eq!(ts.len(), 4);
+        assert!((ts[0] - 4.).abs() < EPSILON);
+        assert!((ts[1] - (5. - 0.63_f64.sqrt())).abs() < EPSILON);
+        assert!((ts[2] - (5. + 0.63_f64.sqrt())).abs() < EPSILON);
+        assert!((ts[3] - 6.).abs() < EPSILON);
+        let comps = xs[0].prepare_computations(&r, &xs);
+        assert_eq!(comps.normalv, Tuple::vector(0., 0., -1.));
+    }
+    // Bounding boxes.
+    #[test]
+    fn default_bounding_box_is_empty() {
+        let b = BoundingBox::default();
+        assert!(b.is_empty());
+        assert_eq!(b.min.x, f64::INFINITY);
+        assert_eq!(b.max.x, f64::NEG_INFINITY);
+    }
+    #[test]
+    fn add_points_to_bounding_box() {
+        let mut b = BoundingBox::default();
+        b.add_point(Tuple::point(-5., 2., 0.));
+        b.add_point(Tuple::point(7., 0., -3.));
+        assert_eq!(b.min, Tuple::point(-5., 0., -3.));
+        assert_eq!(b.max, Tuple::point(7., 2., 0.));
+    }
+    #[test]
+    fn merge_bounding_boxes() {
+        let mut b1 = BoundingBox::new(Tuple::point(-5., -2., 0.), Tuple::point(7., 4., 4.));
+        let b2 = BoundingBox::new(Tuple::point(8., -7., -2.), Tuple::point(14., 2., 8.));
+        b1.merge(&b2);
+        assert_eq!(b1.min, Tuple::point(-5., -7., -2.));
+        assert_eq!(b1.max, Tuple::point(14., 4., 8.));
+        b1.merge(&BoundingBox::default());
+        assert_eq!(b1.min, Tuple::point(-5., -7., -2.));
+    }
+    #[test]
+    fn bounding_box_contains() {
+        let b = BoundingBox::new(Tuple::point(5., -2., 0.), Tuple::point(11., 4., 7.));
+        for (point, result) in [
+            (Tuple::point(5., -2., 0.), true),
+            (Tuple::point(11., 4., 7.), true),
+            (Tuple::point(8., 1., 3.), true),
+            (Tuple::point(3., 0., 3.), false),
+            (Tuple::point(8., -4., 3.), false),
+            (Tuple::point(8., 1., -1.), false),
+            (Tuple::point(13., 1., 3.), false),
+            (Tuple::point(8., 5., 3.), false),
+            (Tuple::point(8., 1., 8.), false),
+        ] {
+            assert_eq!(b.contains_point(point), result);
+        }
+        for (min, max, result) in [
+            (Tuple::point(5., -2., 0.), Tuple::point(11., 4., 7.), true),
+            (Tuple::point(6., -1., 1.), Tuple::point(10., 3., 6.), true),
+            (Tuple::point(4., -3., -1.), Tuple::point(10., 3., 6.), false),
+            (Tuple::point(6., -1., 1.), Tuple::point(12., 5., 8.), false),
+        ] {
+            assert_eq!(b.contains_box(&BoundingBox::new(min, max)), result);
+        }
+    }
+    #[test]
+    fn transform_bounding_box() {
+        let b = BoundingBox::new(Tuple::point(-1., -1., -1.), Tuple::point(1., 1., 1.));
+        let b = b.transform(Matrix4x4::rotation_x(FRAC_PI_4) * Matrix4x4::rotation_y(FRAC_PI_4));
+        assert_eq!(b.min, Tuple::point(-SQRT_2, -1.70711, -1.70711));
+        assert_eq!(b.max, Tuple::point(SQRT_2, 1.70711, 1.70711));
+    }
+    #[test]
+    fn transform_infinite_bounding_box() {
+        let b = Plane.bounds().transform(Matrix4x4::translation(0., 3., 0.));
+        assert_eq!(b.min.x, f64::NEG_INFINITY);
+        assert_eq!(b.max.z, f64::INFINITY);
+        assert_eq!((b.min.y, b.max.y), (3., 3.));
+        let b = Plane.bounds().transform(Matrix4x4::rotation_x(FRAC_PI_2));
+        assert!([b.min.x, b.min.y, b.min.z]
+            .iter()
+            .all(|c| *c == f64::NEG_INFINITY));
+        assert!([b.max.x, b.max.y, b.max.z]
+            .iter()
+            .all(|c| *c == f64::INFINITY));
+    }
+    #[test]
+    fn primitive_bounds() {
+        let unit = BoundingBox::new(Tuple::point(-1., -1., -1.), Tuple::point(1., 1., 1.));
+        assert_eq!(Sphere.bounds(), unit);
+        assert_eq!(Cube.bounds(), unit);
+        let b = Plane.bounds();
+        assert_eq!(
+            (b.min.x, b.min.y, b.min.z),
+            (f64::NEG_INFINITY, 0., f64::NEG_INFINITY)
+        );
+        assert_eq!(
+            (b.max.x, b.max.y, b.max.z),
+            (f64::INFINITY, 0., f64::INFINITY)
+        );
+        assert_eq!(
+            Cylinder::new(-5., 3., false).bounds(),
+            BoundingBox::new(Tuple::point(-1., -5., -1.), Tuple::point(1., 3., 1.))
+        );
+        assert_eq!(
+            Cone::new(-5., 3., false).bounds(),
+            BoundingBox::new(Tuple::point(-5., -5., -5.), Tuple::point(5., 3., 5.))
+        );
+        let b = Cylinder::default().bounds();
+        assert_eq!((b.min.y, b.max.y), (f64::NEG_INFINITY, f64::INFINITY));
+        let t = Triangle::new(
+            Tuple::point(-3., 7., 2.),
+            Tuple::point(6., 2., -4.),
+            Tuple::point(2., -1., -1.),
+        );
+        assert_eq!(
+            t.bounds(),
+            BoundingBox::new(Tuple::point(-3., -1., -4.), Tuple::point(6., 7., 2.))
+        );
+    }
+    #[test]
+    fn parent_space_bounds() {
+        let s = Object::sphere(
+            (Matrix4x4::translation(1., -3., 5.) * Matrix4x4::scaling(0.5, 2., 4.)).inverse(),
+            Material::default(),
+        );
+        assert_eq!(
+            s.parent_space_bounds(),
+            BoundingBox::new(Tuple::point(0.5, -5., 1.), Tuple::point(1.5, -1., 9.))
+        );
+    }
+    #[test]
+    fn group_bounds_contain_children() {
+        let s = Object::sphere(
+            (Matrix4x4::translation(2., 5., -3.) * Matrix4x4::scaling(2., 2., 2.)).inverse(),
+            Material::default(),
+        );
+        let c = Object::new(
+            Cylinder::new(-2., 2., false),
+            (Matrix4x4::translation(-4., -1., 4.) * Matrix4x4::scaling(0.5, 1., 0.5)).inverse(),
+            Material::default(),
+        );
+        let g = Group::new(vec![s, c]);
+        assert_eq!(
+            g.bounds(),
+            BoundingBox::new(Tuple::point(-4.5, -3., -5.), Tuple::point(4., 7., 4.5))
+        );
+    }
+    #[test]
+    fn csg_bounds_contain_operands() {
+        let c = Csg::new(
+            Operation::Difference,
+            Object::sphere(Matrix4x4::_IDENTITY, Material::default()),
+            Object::sphere(
+                Matrix4x4::translation(2., 3., 4.).inverse(),
+                Material::default(),
+            ),
+        );
+        assert_eq!(
+            c.bounds(),
+            BoundingBox::new(Tuple::point(-1., -1., -1.), Tuple::point(3., 4., 5.))
+        );
+    }
+    #[test]
+    fn ray_intersects_bounding_box() {
+        let b = BoundingBox::new(Tuple::point(5., -2., 0.), Tuple::point(11., 4., 7.));
+        for (origin, direction, result) in [
+            (Tuple::point(15., 1., 2.), Tuple::vector(-1., 0., 0.), true),
+            (Tuple::point(-5., -1., 4.), Tuple::vector(1., 0., 0.), true),
+            (Tuple::point(7., 6., 5.), Tuple::vector(0., -1., 0.), true),
+            (Tuple::point(9., -5., 6.), Tuple::vector(0., 1., 0.), true),
+            (Tuple::point(8., 2., 12.), Tuple::vector(0., 0., -1.), true),
+            (Tuple::point(6., 0., -5.), Tuple::vector(0., 0., 1.), true),
+            (Tuple::point(8., 1., 3.5), Tuple::vector(0., 0., 1.), true),
+            (Tuple::point(9., -1., -8.), Tuple::vector(2., 4., 6.), false),
+            (Tuple::point(8., 3., -4.), Tuple::vector(6., 2., 4.), false),
+            (Tuple::point(9., -1., -2.), Tuple::vector(4., 6., 2.), false),
+            (Tuple::point(4., 0., 9.), Tuple::vector(0., 0., -1.), false),
+            (Tuple::point(8., 6., -1.), Tuple::vector(0., -1., 0.), false),
+            (Tuple::point(12., 5., 4.), Tuple::vector(-1., 0., 0.), false),
+        ] {
+            let r = Ray::new(origin, direction.normalize());
+            assert_eq!(b.intersects(&r), result);
+        }
+    }
+    #[test]
+    fn group_skips_children_when_ray_misses_bounds() {
+        let child = TestShape::default();
+        let saved_ray = Rc::clone(&child.saved_ray);
+        let g = Object::new(
+            Group::new(vec![Object::new(
+                child,
+                Matrix4x4::_IDENTITY,
+                Material::default(),
+            )]),
+            Matrix4x4::_IDENTITY,
+            Material::default(),
+        );
+        let r = Ray::new(Tuple::point(0., 0., -5.), Tuple::vector(0., 1., 0.));
+        g.intersect(r);
+        assert!(saved_ray.get().is_none());
+        let r = Ray::new(Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.));
+        g.intersect(r);
+        assert!(saved_ray.get().is_some());
+    }
+    // Bounding volume hierarchies.
+    /// A row of `n` unit spheres along the x axis, three units apart.
+    fn sphere_row(n: usize) -> Vec<Object> {
+        (0..n)
+            .map(|i| {
+                Object::sphere(
+                    Matrix4x4::translation(3. * i as f64, 0., 0.).inverse(),
+                    Material::default(),
+                )
+            })
+            .collect()
+    }
+    #[test]
+    fn empty_bvh() {
+        let b = Object::new(Bvh::new(vec![]), Matrix4x4::_IDENTITY, Material::default());
+        let r = Ray::new(Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.));
+        assert!(b.intersect(r).is_empty());
+        assert!(!b.intersects_before(r, f64::INFINITY));
+        assert!(b.shape().bounds().is_empty());
+        assert_eq!(b.shape_as::<Bvh>().unwrap().stats().node_count, 0);
+    }
+    #[test]
+    fn bvh_keeps_coincident_objects_in_one_leaf() {
+        let stats = Bvh::new(
+            (0..3)
+                .map(|_| Object::sphere(Matrix4x4::_IDENTITY, Material::default()))
+                .collect(),
+        )
+        .stats();
+        assert_eq!(stats.node_count, 1);
+        assert_eq!(stats.leaf_count, 1);
+        assert_eq!(stats.depth, 1);
+        assert_eq!(stats.average_leaf_size, 3.);
+    }
+    #[test]
+    fn bvh_splits_spread_out_objects() {
+        let b = Bvh::new(sphere_row(64));
+        let stats = b.stats();
+        assert_eq!(b.objects().len(), 64);
+        assert!(stats.leaf_count > 1);
+        assert_eq!(stats.node_count, 2 * stats.leaf_count - 1);
+        assert!(stats.depth >= 4 && stats.depth < 64);
+        assert_eq!(stats.average_leaf_size, 64. / stats.leaf_count as f64);
+        assert_eq!(
+            b.bounds(),
+            BoundingBox::new(Tuple::point(-1., -1., -1.), Tuple::point(190., 1., 1.))
+        );
+    }
+    #[test]
+    fn bvh_finds_the_same_intersections_as_a_group() {
+        let b = Object::new(
+            Bvh::new(sphere_row(20)),
+            Matrix4x4::_IDENTITY,
+            Material::default(),
+        );
+        let g = Object::new(
+            Group::new(sphere_row(20)),
+            Matrix4x4::_IDENTITY,
+            Material::default(),
+        );
+        for r in [
+            Ray::new(Tuple::point(-5., 0., 0.), Tuple::vector(1., 0., 0.)),
+            Ray::new(Tuple::point(100., 0., 0.), Tuple::vector(-1., 0., 0.)),
+            Ray::new(Tuple::point(30., 0., -5.), Tuple::vector(0., 0., 1.)),
+            Ray::new(Tuple::point(0., 0.5, -5.), Tuple::vector(3., 0., 1.)),
+            Ray::new(Tuple::point(0., 5., 0.), Tuple::vector(1., 0., 0.)),
+        ] {
+            let bts: Vec<f64> = b.intersect(r).iter().map(|i| i.t()).collect();
+            let gts: Vec<f64> = g.intersect(r).iter().map(|i| i.t()).collect();
+            assert_eq!(bts, gts);
+        }
+    }
+    #[test]
+    fn bvh_intersections_refer_to_its_objects() {
+        let b = Object::new(
+            Bvh::new(sphere_row(8)),
+            Matrix4x4::_IDENTITY,
+            Material::default(),
+        );
+        let r = Ray::new(Tuple::point(9., 0., -5.), Tuple::vector(0., 0., 1.));
+        let xs = b.intersect(r);
+        assert_eq!(xs.len(), 2);
+        assert!(b.includes(xs[0].object()));
+        assert_eq!(
+            xs[0].object().world_to_object(Tuple::point(9., 0., -1.)),
+            Tuple::point(0., 0., -1.)
+        );
+    }
+    #[test]
+    fn bvh_passes_its_transform_to_objects() {
+        let b = Object::new(
+            Bvh::new(sphere_row(8)),
+            Matrix4x4::scaling(2., 2., 2.).inverse(),
+            Material::default(),
+        );
+        let r = Ray::new(Tuple::point(18., 0., -10.), Tuple::vector(0., 0., 1.));
+        let xs = b.intersect(r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t(), 8.);
+        let n = xs[0].object().normal_at(Tuple::point(18., 0., -2.));
+        assert_eq!(n, Tuple::vector(0., 0., -1.));
+    }
+    #[test]
+    fn bvh_shadow_rays_stop_at_distance() {
+        let b = Object::new(
+            Bvh::new(sphere_row(8)),
+            Matrix4x4::_IDENTITY,
+            Material::default(),
+        );
+        let r = Ray::new(Tuple::point(-5., 0., 0.), Tuple::vector(1., 0., 0.));
+        assert!(b.intersects_before(r, 5.));
+        assert!(!b.intersects_before(r, 4.));
+        let r = Ray::new(Tuple::point(30., 0., 0.), Tuple::vector(1., 0., 0.));
+        assert!(!b.intersects_before(r, f64::INFINITY));
+    }
+    #[test]
+    fn bvh_casts_shadows() {
+        let mut w = World::default_world();
+        w.objects = vec![Object::new(
+            Bvh::new(sphere_row(4)),
+            Matrix4x4::_IDENTITY,
+            Material::default(),
+        )];
+        assert!(w.is_shadowed(Tuple::point(10., -10., 10.), &w.lights[0]));
+        assert!(!w.is_shadowed(Tuple::point(20., 10., 0.), &w.lights[0]));
+    }
+    // Surface normals.
+    #[test]
+    fn sphere_normal_on_axis() {
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        assert_eq!(
+            s.normal_at(Tuple::point(1., 0., 0.)),
+            Tuple::vector(1., 0., 0.)
+        );
+        assert_eq!(
+            s.normal_at(Tuple::point(0., 1., 0.)),
+            Tuple::vector(0., 1., 0.)
+        );
+        assert_eq!(
+            s.normal_at(Tuple::point(0., 0., 1.)),
+            Tuple::vector(0., 0., 1.)
+        );
+    }
+    #[test]
+    fn sphere_normal_nonaxial() {
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let v = 3.0_f64.sqrt() / 3.;
+        let n = s.normal_at(Tuple::point(v, v, v));
+        assert_eq!(n, Tuple::vector(v, v, v));
+        assert_eq!(n, n.normalize());
+    }
+    #[test]
+    fn translated_sphere_normal() {
+        let s = Object::sphere(
+            Matrix4x4::translation(0., 1., 0.).inverse(),
+            Material::default(),
+        );
+        let n = s.normal_at(Tuple::point(0., 1. + FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+        assert_eq!(n, Tuple::vector(0., FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+    }
+    #[test]
+    fn transformed_sphere_normal() {
+        let s = Object::sphere(
+            (Matrix4x4::scaling(1., 0.5, 1.) * Matrix4x4::rotation_z(PI / 5.)).inverse(),
+            Material::default(),
+        );
+        let n = s.normal_at(Tuple::point(
+            0.,
+            2.0_f64.sqrt() / 2.,
+            -(2.0_f64.sqrt()) / 2.,
+        ));
+        assert_eq!(n, Tuple::vector(0., 0.97014, -0.24254));
+    }
+    #[test]
+    fn reflect_vector_45_degrees() {
+        let v = Tuple::vector(1., -1., 0.);
+        let n = Tuple::vector(0., 1., 0.);
+        assert_eq!(Tuple::reflect(v, n), Tuple::vector(1., 1., 0.));
+    }
+    #[test]
+    fn reflect_vector_slanted_surface() {
+        let v = Tuple::vector(0., -1., 0.);
+        let n = Tuple::vector(2.0_f64.sqrt() / 2., 2.0_f64.sqrt() / 2., 0.);
+        assert_eq!(Tuple::reflect(v, n), Tuple::vector(1., 0., 0.));
+    }
+    // Lighting.
+    #[test]
+    fn point_light_position_intensity() {
+        let intensity = Color::new(1., 1., 1.);
+        let position = Tuple::point(0., 0., 0.);
+        let light = PointLight::new(position, intensity);
+        assert_eq!(light.position, position);
+        assert_eq!(light.intensity, intensity);
+    }
+    #[test]
+    fn default_material() {
+        let m = Material::default();
+        assert_eq!(m.color, Color::new(1., 1., 1.));
+        assert_eq!(m.ambient, 0.1);
+        assert_eq!(m.diffuse, 0.9);
+        assert_eq!(m.specular, 0.9);
+        assert_eq!(m.shininess, 200.);
+    }
+    #[test]
+    fn sphere_material() {
+        let m = Material {
+            ambient: 1.,
+            ..Material::default()
+        };
+        let s = Object::sphere(Matrix4x4::_IDENTITY, m.clone());
+        assert_eq!(*s.material(), m);
+    }
+    #[test]
+    fn lighting_eye_between_light_and_surface() {
+        let m = Material::default();
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let position = Tuple::point(0., 0., 0.);
+        let eyev = Tuple::vector(0., 0., -1.);
+        let normalv = Tuple::vector(0., 0., -1.);
+        let light = PointLight::new(Tuple::point(0., 0., -10.), Color::new(1., 1., 1.));
+        assert_eq!(
+            lighting(&m, &s, &light, position, eyev, normalv, false),
+            Color::new(1.9, 1.9, 1.9)
+        );
+    }
+    #[test]
+    fn lighting_eye_offset_45_degrees() {
+        let m = Material::default();
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let position = Tuple::point(0., 0., 0.);
+        let eyev = Tuple::vector(0., FRAC_1_SQRT_2, -FRAC_1_SQRT_2);
+        let normalv = Tuple::vector(0., 0., -1.);
+        let light = PointLight::new(Tuple::point(0., 0., -10.), Color::new(1., 1., 1.));
+        assert_eq!(
+            lighting(&m, &s, &light, position, eyev, normalv, false),
+            Color::new(1., 1., 1.)
+        );
+    }
+    #[test]
+    fn lighting_light_offset_45_degrees() {
+        let m = Material::default();
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let position = Tuple::point(0., 0., 0.);
+        let eyev = Tuple::vector(0., 0., -1.);
+        let normalv = Tuple::vector(0., 0., -1.);
+        let light = PointLight::new(Tuple::point(0., 10., -10.), Color::new(1., 1., 1.));
+        assert_eq!(
+            lighting(&m, &s, &light, position, eyev, normalv, false),
+            Color::new(0.7364, 0.7364, 0.7364)
+        );
+    }
+    #[test]
+    fn lighting_eye_in_reflection_path() {
+        let m = Material::default();
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let position = Tuple::point(0., 0., 0.);
+        let eyev = Tuple::vector(0., -FRAC_1_SQRT_2, -FRAC_1_SQRT_2);
+        let normalv = Tuple::vector(0., 0., -1.);
+        let light = PointLight::new(Tuple::point(0., 10., -10.), Color::new(1., 1., 1.));
+        assert_eq!(
+            lighting(&m, &s, &light, position, eyev, normalv, false),
+            Color::new(1.6364, 1.6364, 1.6364)
+        );
+    }
+    #[test]
+    fn lighting_light_behind_surface() {
+        let m = Material::default();
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let position = Tuple::point(0., 0., 0.);
+        let eyev = Tuple::vector(0., 0., -1.);
+        let normalv = Tuple::vector(0., 0., -1.);
+        let light = PointLight::new(Tuple::point(0., 0., 10.), Color::new(1., 1., 1.));
+        assert_eq!(
+            lighting(&m, &s, &light, position, eyev, normalv, false),
+            Color::new(0.1, 0.1, 0.1)
+        );
+    }
+    // Precomputed hit state.
+    #[test]
+    fn precompute_intersection_state() {
+        let r = Ray::new(Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.));
+        let shape = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let i = Intersection::new(4., &shape);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert_eq!(comps.t, i.t());
+        assert_eq!(comps.object, &shape);
+        assert_eq!(comps.point, Tuple::point(0., 0., -1.));
+        assert_eq!(comps.eyev, Tuple::vector(0., 0., -1.));
+        assert_eq!(comps.normalv, Tuple::vector(0., 0., -1.));
+    }
+    #[test]
+    fn precompute_hit_outside() {
+        let r = Ray::new(Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.));
+        let shape = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let i = Intersection::new(4., &shape);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert!(!comps.inside);
+    }
+    #[test]
+    fn precompute_hit_inside() {
+        let r = Ray::new(Tuple::point(0., 0., 0.), Tuple::vector(0., 0., 1.));
+        let shape = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let i = Intersection::new(1., &shape);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert_eq!(comps.point, Tuple::point(0., 0., 1.));
+        assert_eq!(comps.eyev, Tuple::vector(0., 0., -1.));
+        assert!(comps.inside);
+        assert_eq!(comps.normalv, Tuple::vector(0., 0., -1.));
+    }
+    #[test]
+    fn precompute_over_point() {
+        let r = Ray::new(Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.));
+        let shape = Object::sphere(
+            Matrix4x4::translation(0., 0., 1.).inverse(),
+            Material::default(),
+        );
+        let i = Intersection::new(5., &shape);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert!(comps.over_point.z < -EPSILON / 2.);
+        assert!(comps.point.z > comps.over_point.z);
+    }
+    #[test]
+    fn precompute_under_point() {
+        let r = Ray::new(Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.));
+        let shape = Object::sphere(
+            Matrix4x4::translation(0., 0., 1.).inverse(),
+            Material::default(),
+        );
+        let i = Intersection::new(5., &shape);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert!(comps.under_point.z > EPSILON / 2.);
+        assert!(comps.point.z < comps.under_point.z);
+    }
+    #[test]
+    fn shade_intersection() {
+        let w = World::default_world();
+        let r = Ray::new(Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.));
+        let i = Intersection::new(4., &w.objects[0]);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert_eq!(w.shade_hit(&comps, 5), Color::new(0.38066, 0.47583, 0.2855));
+    }
+    #[test]
+    fn shade_intersection_from_inside() {
+        let mut w = World::default_world();
+        w.lights = vec![PointLight::new(
+            Tuple::point(0., 0.25, 0.),
+            Color::new(1., 1., 1.),
+        )];
+        let r = Ray::new(Tuple::point(0., 0., 0.), Tuple::vector(0., 0., 1.));
+        let i = Intersection::new(0.5, &w.objects[1]);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert_eq!(
+            w.shade_hit(&comps, 5),
+            Color::new(0.90498, 0.90498, 0.90498)
+        );
+    }
+    // World.
+    #[test]
+    fn default_world() {
+        let w = World::default_world();
+        let light = PointLight::new(Tuple::point(-10., 10., -10.), Color::new(1., 1., 1.));
+        assert_eq!(w.lights, vec![light]);
+        assert_eq!(w.objects.len(), 2);
+        assert_eq!(w.objects[0].material().color, Color::new(0.8, 1., 0.6));
+        assert_eq!(w.objects[0].material().diffuse, 0.7);
+        assert_eq!(w.objects[0].material().specular, 0.2);
+        assert_eq!(
+            w.objects[1].transform(),
+            Matrix4x4::scaling(0.5, 0.5, 0.5).inverse()
+        );
+        assert_eq!(*w.objects[1].material(), Material::default());
+    }
+    #[test]
+    fn intersect_world_with_ray() {
+        let w = World::default_world();
+        let r = Ray::new(Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.));
+        let xs: Vec<f64> = w.intersect_world(&r).iter().map(|i| i.t()).collect();
+        assert_eq!(xs, vec![4., 4.5, 5.5, 6.]);
+    }
+    #[test]
+    fn color_when_ray_misses() {
+        let w = World::default_world();
+        let r = Ray::new(Tuple::point(0., 0., -5.), Tuple::vector(0., 1., 0.));
+        assert_eq!(w.color_at(&r, 5), Color::new(0., 0., 0.));
+    }
+    #[test]
+    fn color_when_ray_hits() {
+        let w = World::default_world();
+        let r = Ray::new(Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.));
+        assert_eq!(w.color_at(&r, 5), Color::new(0.38066, 0.47583, 0.2855));
+    }
+    #[test]
+    fn color_with_intersection_behind_ray() {
+        let mut w = World::default_world();
+        w.objects[0].material_mut().ambient = 1.;
+        w.objects[1].material_mut().ambient = 1.;
+        let r = Ray::new(Tuple::point(0., 0., 0.75), Tuple::vector(0., 0., -1.));
+        assert_eq!(w.color_at(&r, 5), w.objects[1].material().color);
+    }
+    // View transformations.
+    #[test]
+    fn view_transform_default_orientation() {
+        let t = Matrix4x4::view_transform(
+            Tuple::point(0., 0., 0.),
+            Tuple::point(0., 0., -1.),
+            Tuple::vector(0., 1., 0.),
+        );
+        assert_eq!(t, Matrix4x4::_IDENTITY);
+    }
+    #[test]
+    fn view_transform_looking_positive_z() {
+        let t = Matrix4x4::view_transform(
+            Tuple::point(0., 0., 0.),
+            Tuple::point(0., 0., 1.),
+            Tuple::vector(0., 1., 0.),
+        );
+        assert_eq!(t, Matrix4x4::scaling(-1., 1., -1.));
+    }
+    #[test]
+    fn view_transform_moves_world() {
+        let t = Matrix4x4::view_transform(
+            Tuple::point(0., 0., 8.),
+            Tuple::point(0., 0., 0.),
+            Tuple::vector(0., 1., 0.),
+        );
+        assert_eq!(t, Matrix4x4::translation(0., 0., -8.));
+    }
+    #[test]
+    fn view_transform_arbitrary() {
+        let t = Matrix4x4::view_transform(
+            Tuple::point(1., 3., 2.),
+            Tuple::point(4., -2., 8.),
+            Tuple::vector(1., 1., 0.),
+        );
+        assert_eq!(
+            t,
+            Matrix4x4::new([
+                [-0.50709, 0.50709, 0.67612, -2.36643],
+                [0.76772, 0.60609, 0.12122, -2.82843],
+                [-0.35857, 0.59761, -0.71714, 0.],
+                [0., 0., 0., 1.],
+            ])
+        );
+    }
+    // Camera.
+    #[test]
+    fn construct_camera() {
+        let c = Camera::new(160, 120, FRAC_PI_2, Matrix4x4::_IDENTITY);
+        assert_eq!(c.get_hsize(), 160);
+        assert_eq!(c.get_vsize(), 120);
+        assert_eq!(c.get_field_of_view(), FRAC_PI_2);
+        assert_eq!(c.get_transform(), Matrix4x4::_IDENTITY);
+    }
+    #[test]
+    fn camera_pixel_size() {
+        let c = Camera::new(200, 125, FRAC_PI_2, Matrix4x4::_IDENTITY);
+        assert!((c.get_pixel_size() - 0.01).abs() < 1e-5);
+        let c = Camera::new(125, 200, FRAC_PI_2, Matrix4x4::_IDENTITY);
+        assert!((c.get_pixel_size() - 0.01).abs() < 1e-5);
+    }
+    #[test]
+    fn camera_ray_through_center() {
+        let c = Camera::new(201, 101, FRAC_PI_2, Matrix4x4::_IDENTITY);
+        let r = c.ray_for_pixel(100, 50);
+        assert_eq!(r.origin, Tuple::point(0., 0., 0.));
+        assert_eq!(r.direction, Tuple::vector(0., 0., -1.));
+    }
+    #[test]
+    fn camera_ray_through_corner() {
+        let c = Camera::new(201, 101, FRAC_PI_2, Matrix4x4::_IDENTITY);
+        let r = c.ray_for_pixel(0, 0);
+        assert_eq!(r.origin, Tuple::point(0., 0., 0.));
+        assert_eq!(r.direction, Tuple::vector(0.66519, 0.33259, -0.66851));
+    }
+    #[test]
+    fn camera_ray_when_transformed() {
+        let c = Camera::new(
+            201,
+            101,
+            FRAC_PI_2,
+            Matrix4x4::rotation_y(FRAC_PI_4) * Matrix4x4::translation(0., -2., 5.),
+        );
+        let r = c.ray_for_pixel(100, 50);
+        assert_eq!(r.origin, Tuple::point(0., 2., -5.));
+        assert_eq!(
+            r.direction,
+            Tuple::vector(FRAC_1_SQRT_2, 0., -FRAC_1_SQRT_2)
+        );
+    }
+    #[test]
+    fn render_world_with_camera() {
+        let w = World::default_world();
+        let c = Camera::new(
+            11,
+            11,
+            FRAC_PI_2,
+            Matrix4x4::view_transform(
+                Tuple::point(0., 0., -5.),
+                Tuple::point(0., 0., 0.),
+                Tuple::vector(0., 1., 0.),
+            ),
+        );
+        let image = c.render(&w);
+        assert_eq!(image.pixel_at(5, 5), Color::new(0.38066, 0.47583, 0.2855));
+    }
+    // Shadows.
+    #[test]
+    fn lighting_surface_in_shadow() {
+        let m = Material::default();
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let position = Tuple::point(0., 0., 0.);
+        let eyev = Tuple::vector(0., 0., -1.);
+        let normalv = Tuple::vector(0., 0., -1.);
+        let light = PointLight::new(Tuple::point(0., 0., -10.), Color::new(1., 1., 1.));
+        assert_eq!(
+            lighting(&m, &s, &light, position, eyev, normalv, true),
+            Color::new(0.1, 0.1, 0.1)
+        );
+    }
+    #[test]
+    fn no_shadow_when_nothing_collinear() {
+        let w = World::default_world();
+        assert!(!w.is_shadowed(Tuple::point(0., 10., 0.), &w.lights[0]));
+    }
+    #[test]
+    fn shadow_when_object_between_point_and_light() {
+        let w = World::default_world();
+        assert!(w.is_shadowed(Tuple::point(10., -10., 10.), &w.lights[0]));
+    }
+    #[test]
+    fn no_shadow_when_light_between_point_and_object() {
+        let w = World::default_world();
+        assert!(!w.is_shadowed(Tuple::point(-20., 20., -20.), &w.lights[0]));
+    }
+    #[test]
+    fn no_shadow_when_point_behind_object() {
+        let w = World::default_world();
+        assert!(!w.is_shadowed(Tuple::point(-2., 2., -2.), &w.lights[0]));
+    }
+    #[test]
+    fn shade_hit_in_shadow() {
+        let w = World::new(
+            vec![
+                Object::sphere(Matrix4x4::_IDENTITY, Material::default()),
+                Object::sphere(
+                    Matrix4x4::translation(0., 0., 10.).inverse(),
+                    Material::default(),
+                ),
+            ],
+            vec![PointLight::new(
+                Tuple::point(0., 0., -10.),
+                Color::new(1., 1., 1.),
+            )],
+        );
+        let r = Ray::new(Tuple::point(0., 0., 5.), Tuple::vector(0., 0., 1.));
+        let i = Intersection::new(4., &w.objects[1]);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert_eq!(w.shade_hit(&comps, 5), Color::new(0.1, 0.1, 0.1));
+    }
+    // Reflections.
+    #[test]
+    fn default_material_is_not_reflective() {
+        assert_eq!(Material::default().reflective, 0.);
+    }
+    #[test]
+    fn precompute_reflection_vector() {
+        let p = Object::new(Plane, Matrix4x4::_IDENTITY, Material::default());
+        let r = Ray::new(
+            Tuple::point(0., 1., -1.),
+            Tuple::vector(0., -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        );
+        let i = Intersection::new(SQRT_2, &p);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert_eq!(
+            comps.reflectv,
+            Tuple::vector(0., FRAC_1_SQRT_2, FRAC_1_SQRT_2)
+        );
+    }
+    #[test]
+    fn reflected_color_for_nonreflective_material() {
+        let mut w = World::default_world();
+        w.objects[1].material_mut().ambient = 1.;
+        let r = Ray::new(Tuple::point(0., 0., 0.), Tuple::vector(0., 0., 1.));
+        let i = Intersection::new(1., &w.objects[1]);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert_eq!(w.reflected_color(&comps, 5), Color::new(0., 0., 0.));
+    }
+    /// The default world with a half-mirrored floor one unit below it.
+    fn world_with_reflective_floor() -> World {
+        let mut w = World::default_world();
+        w.objects.push(Object::new(
+            Plane,
+            Matrix4x4::translation(0., -1., 0.).inverse(),
+            Material {
+                reflective: 0.5,
+                ..Material::default()
+            },
+        ));
+        w
+    }
+    #[test]
+    fn reflected_color_for_reflective_material() {
+        let w = world_with_reflective_floor();
+        let r = Ray::new(
+            Tuple::point(0., 0., -3.),
+            Tuple::vector(0., -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        );
+        let i = Intersection::new(SQRT_2, &w.objects[2]);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert_eq!(
+            w.reflected_color(&comps, 5),
+            Color::new(0.19033, 0.23791, 0.14274)
+        );
+    }
+    #[test]
+    fn shade_hit_with_reflective_material() {
+        let w = world_with_reflective_floor();
+        let r = Ray::new(
+            Tuple::point(0., 0., -3.),
+            Tuple::vector(0., -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        );
+        let i = Intersection::new(SQRT_2, &w.objects[2]);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert_eq!(
+            w.shade_hit(&comps, 5),
+            Color::new(0.87676, 0.92434, 0.82918)
+        );
+    }
+    #[test]
+    fn reflected_color_at_maximum_depth() {
+        let w = world_with_reflective_floor();
+        let r = Ray::new(
+            Tuple::point(0., 0., -3.),
+            Tuple::vector(0., -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        );
+        let i = Intersection::new(SQRT_2, &w.objects[2]);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert_eq!(w.reflected_color(&comps, 0), Color::new(0., 0., 0.));
+    }
+    #[test]
+    fn mutually_reflective_surfaces_terminate() {
+        let mirror = Material {
+            reflective: 1.,
+            ..Material::default()
+        };
+        let w = World::new(
+            vec![
+                Object::new(
+                    Plane,
+                    Matrix4x4::translation(0., -1., 0.).inverse(),
+                    mirror.clone(),
+                ),
+                Object::new(Plane, Matrix4x4::translation(0., 1., 0.).inverse(), mirror),
+            ],
+            vec![PointLight::new(
+                Tuple::point(0., 0., 0.),
+                Color::new(1., 1., 1.),
+            )],
+        );
+        let r = Ray::new(Tuple::point(0., 0., 0.), Tuple::vector(0., 1., 0.));
+        // Each bounce adds the same lit color, so the depth limit decides the
+        // total.
+        let once = w.color_at(&r, 0);
+        assert_eq!(w.color_at(&r, w.max_depth), once * 6.);
+    }
+    #[test]
+    fn camera_renders_with_world_depth_limit() {
+        let mut w = world_with_reflective_floor();
+        let c = Camera::new(
+            1,
+            1,
+            FRAC_PI_2,
+            Matrix4x4::view_transform(
+                Tuple::point(0., 0., -3.),
+                Tuple::point(0., -1., -2.),
+                Tuple::vector(0., 1., 0.),
+            ),
+        );
+        let r = c.ray_for_pixel(0, 0);
+        assert_eq!(c.render(&w).pixel_at(0, 0), w.color_at(&r, 5));
+        w.max_depth = 0;
+        assert_eq!(c.render(&w).pixel_at(0, 0), w.color_at(&r, 0));
+        assert_ne!(w.color_at(&r, 0), w.color_at(&r, 5));
+    }
+    // Refraction.
+    /// A unit sphere of glass.
+    fn glass_sphere(transform: Matrix4x4) -> Object {
+        Object::sphere(
+            transform,
+            Material {
+                transparency: 1.,
+                refractive_index: 1.5,
+                ..Material::default()
+            },
+        )
+    }
+    #[test]
+    fn default_material_is_opaque() {
+        let m = Material::default();
+        assert_eq!(m.transparency, 0.);
+        assert_eq!(m.refractive_index, 1.);
+    }
+    #[test]
+    fn n1_and_n2_at_various_intersections() {
+        let mut a = glass_sphere(Matrix4x4::scaling(2., 2., 2.).inverse());
+        a.material_mut().refractive_index = 1.5;
+        let mut b = glass_sphere(Matrix4x4::translation(0., 0., -0.25).inverse());
+        b.material_mut().refractive_index = 2.;
+        let mut c = glass_sphere(Matrix4x4::translation(0., 0., 0.25).inverse());
+        c.material_mut().refractive_index = 2.5;
+        let r = Ray::new(Tuple::point(0., 0., -4.), Tuple::vector(0., 0., 1.));
+        let xs = Intersections::new(vec![
+            Intersection::new(2., &a),
+            Intersection::new(2.75, &b),
+            Intersection::new(3.25, &c),
+            Intersection::new(4.75, &b),
+            Intersection::new(5.25, &c),
+            Intersection::new(6., &a),
+        ]);
+        for (index, n1, n2) in [
+            (0, 1., 1.5),
+            (1, 1.5, 2.),
+            (2, 2., 2.5),
+            (3, 2.5, 2.5),
+            (4, 2.5, 1.5),
+            (5, 1.5, 1.),
+        ] {
+            let comps = xs[index].prepare_computations(&r, &xs);
+            assert_eq!((comps.n1, comps.n2), (n1, n2));
+        }
+    }
+    #[test]
+    fn refracted_color_of_opaque_surface() {
+        let w = World::default_world();
+        let r = Ray::new(Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.));
+        let xs = Intersections::new(vec![
+            Intersection::new(4., &w.objects[0]),
+            Intersection::new(6., &w.objects[0]),
+        ]);
+        let comps = xs[0].prepare_computations(&r, &xs);
+        assert_eq!(w.refracted_color(&comps, 5), Color::new(0., 0., 0.));
+    }
+    #[test]
+    fn refracted_color_at_maximum_depth() {
+        let mut w = World::default_world();
+        w.objects[0].material_mut().transparency = 1.;
+        w.objects[0].material_mut().refractive_index = 1.5;
+        let r = Ray::new(Tuple::point(0., 0., -5.), Tuple::vector(0., 0., 1.));
+        let xs = Intersections::new(vec![
+            Intersection::new(4., &w.objects[0]),
+            Intersection::new(6., &w.objects[0]),
+        ]);
+        let comps = xs[0].prepare_computations(&r, &xs);
+        assert_eq!(w.refracted_color(&comps, 0), Color::new(0., 0., 0.));
+    }
+    #[test]
+    fn refracted_color_under_total_internal_reflection() {
+        let mut w = World::default_world();
+        w.objects[0].material_mut().transparency = 1.;
+        w.objects[0].material_mut().refractive_index = 1.5;
+        let r = Ray::new(
+            Tuple::point(0., 0., FRAC_1_SQRT_2),
+            Tuple::vector(0., 1., 0.),
+        );
+        let xs = Intersections::new(vec![
+            Intersection::new(-FRAC_1_SQRT_2, &w.objects[0]),
+            Intersection::new(FRAC_1_SQRT_2, &w.objects[0]),
+        ]);
+        // Inside the sphere, so the second intersection is the one to look at.
+        let comps = xs[1].prepare_computations(&r, &xs);
+        assert_eq!(w.refracted_color(&comps, 5), Color::new(0., 0., 0.));
+    }
+    /// The default world with a half-transparent floor and a red ball
+    /// beneath it.
+    fn world_with_transparent_floor(reflective: f64) -> World {
+        let mut w = World::default_world();
+        w.objects.push(Object::new(
+            Plane,
+            Matrix4x4::translation(0., -1., 0.).inverse(),
+            Material {
+                reflective,
+                transparency: 0.5,
+                refractive_index: 1.5,
+                ..Material::default()
+            },
+        ));
+        w.objects.push(Object::sphere(
+            Matrix4x4::translation(0., -3.5, -0.5).inverse(),
+            Material {
+                color: Color::new(1., 0., 0.),
+                ambient: 0.5,
+                ..Material::default()
+            },
+        ));
+        w
+    }
+    #[test]
+    fn shade_hit_with_transparent_material() {
+        let w = world_with_transparent_floor(0.);
+        let r = Ray::new(
+            Tuple::point(0., 0., -3.),
+            Tuple::vector(0., -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        );
+        let xs = Intersections::new(vec![Intersection::new(SQRT_2, &w.objects[2])]);
+        let comps = xs[0].prepare_computations(&r, &xs);
+        assert_eq!(
+            w.shade_hit(&comps, 5),
+            Color::new(0.93642, 0.68642, 0.68642)
+        );
+    }
+    #[test]
+    fn schlick_under_total_internal_reflection() {
+        let shape = glass_sphere(Matrix4x4::_IDENTITY);
+        let r = Ray::new(
+            Tuple::point(0., 0., FRAC_1_SQRT_2),
+            Tuple::vector(0., 1., 0.),
+        );
+        let xs = Intersections::new(vec![
+            Intersection::new(-FRAC_1_SQRT_2, &shape),
+            Intersection::new(FRAC_1_SQRT_2, &shape),
+        ]);
+        assert_eq!(xs[1].prepare_computations(&r, &xs).schlick(), 1.);
+    }
+    #[test]
+    fn schlick_with_perpendicular_ray() {
+        let shape = glass_sphere(Matrix4x4::_IDENTITY);
+        let r = Ray::new(Tuple::point(0., 0., 0.), Tuple::vector(0., 1., 0.));
+        let xs = Intersections::new(vec![
+            Intersection::new(-1., &shape),
+            Intersection::new(1., &shape),
+        ]);
+        let reflectance = xs[1].prepare_computations(&r, &xs).schlick();
+        assert!((reflectance - 0.04).abs() < EPSILON);
+    }
+    #[test]
+    fn schlick_with_small_angle_and_n2_greater_than_n1() {
+        let shape = glass_sphere(Matrix4x4::_IDENTITY);
+        let r = Ray::new(Tuple::point(0., 0.99, -2.), Tuple::vector(0., 0., 1.));
+        let xs = Intersections::new(vec![Intersection::new(1.8589, &shape)]);
+        let reflectance = xs[0].prepare_computations(&r, &xs).schlick();
+        assert!((reflectance - 0.48873).abs() < EPSILON);
+    }
+    #[test]
+    fn shade_hit_with_reflective_transparent_material() {
+        let w = world_with_transparent_floor(0.5);
+        let r = Ray::new(
+            Tuple::point(0., 0., -3.),
+            Tuple::vector(0., -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        );
+        let xs = Intersections::new(vec![Intersection::new(SQRT_2, &w.objects[2])]);
+        let comps = xs[0].prepare_computations(&r, &xs);
+        assert_eq!(
+            w.shade_hit(&comps, 5),
+            Color::new(0.93391, 0.69643, 0.69243)
+        );
+    }
+    // Patterns.
+    /// Returns the pattern space point as a color.
+    #[derive(Debug)]
+    struct TestPattern;
+    impl Pattern for TestPattern {
+        fn local_color_at(&self, point: Tuple) -> Color {
+            Color::new(point.x, point.y, point.z)
+        }
+    }
+    const WHITE: Color = Color::new(1., 1., 1.);
+    const BLACK: Color = Color::new(0., 0., 0.);
+    #[test]
+    fn stripe_pattern() {
+        let pattern = Stripe::new(WHITE, BLACK);
+        let origin = Tuple::point(0., 0., 0.);
+        assert_eq!(pattern.a.color_at(origin), WHITE);
+        assert_eq!(pattern.b.color_at(origin), BLACK);
+        for (point, color) in [
+            (Tuple::point(0., 0., 0.), WHITE),
+            (Tuple::point(0., 1., 0.), WHITE),
+            (Tuple::point(0., 2., 0.), WHITE),
+            (Tuple::point(0., 0., 1.), WHITE),
+            (Tuple::point(0., 0., 2.), WHITE),
+            (Tuple::point(0.9, 0., 0.), WHITE),
+            (Tuple::point(1., 0., 0.), BLACK),
+            (Tuple::point(-0.1, 0., 0.), BLACK),
+            (Tuple::point(-1., 0., 0.), BLACK),
+            (Tuple::point(-1.1, 0., 0.), WHITE),
+        ] {
+            assert_eq!(pattern.local_color_at(point), color);
+        }
+    }
+    #[test]
+    fn lighting_with_pattern() {
+        let m = Material {
+            pattern: Some(Texture::new(
+                Stripe::new(WHITE, BLACK),
+                Matrix4x4::_IDENTITY,
+            )),
+            ambient: 1.,
+            diffuse: 0.,
+            specular: 0.,
+            ..Material::default()
+        };
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let eyev = Tuple::vector(0., 0., -1.);
+        let normalv = Tuple::vector(0., 0., -1.);
+        let light = PointLight::new(Tuple::point(0., 0., -10.), WHITE);
+        let c1 = lighting(
+            &m,
+            &s,
+            &light,
+            Tuple::point(0.9, 0., 0.),
+            eyev,
+            normalv,
+            false,
+        );
+        let c2 = lighting(
+            &m,
+            &s,
+            &light,
+            Tuple::point(1.1, 0., 0.),
+            eyev,
+            normalv,
+            false,
+        );
+        assert_eq!(c1, WHITE);
+        assert_eq!(c2, BLACK);
+    }
+    #[test]
+    fn stripes_with_object_transformation() {
+        let s = Object::sphere(
+            Matrix4x4::scaling(2., 2., 2.).inverse(),
+            Material::default(),
+        );
+        let t = Texture::new(Stripe::new(WHITE, BLACK), Matrix4x4::_IDENTITY);
+        assert_eq!(t.color_at_object(&s, Tuple::point(1.5, 0., 0.)), WHITE);
+    }
+    #[test]
+    fn stripes_with_pattern_transformation() {
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let t = Texture::new(
+            Stripe::new(WHITE, BLACK),
+            Matrix4x4::scaling(2., 2., 2.).inverse(),
+        );
+        assert_eq!(t.color_at_object(&s, Tuple::point(1.5, 0., 0.)), WHITE);
+    }
+    #[test]
+    fn stripes_with_object_and_pattern_transformation() {
+        let s = Object::sphere(
+            Matrix4x4::scaling(2., 2., 2.).inverse(),
+            Material::default(),
+        );
+        let t = Texture::new(
+            Stripe::new(WHITE, BLACK),
+            Matrix4x4::translation(0.5, 0., 0.).inverse(),
+        );
+        assert_eq!(t.color_at_object(&s, Tuple::point(2.5, 0., 0.)), WHITE);
+    }
+    #[test]
+    fn texture_transform() {
+        let mut t = Texture::new(TestPattern, Matrix4x4::_IDENTITY);
+        assert_eq!(t.transform(), Matrix4x4::_IDENTITY);
+        t.set_transform(Matrix4x4::translation(1., 2., 3.));
+        assert_eq!(t.transform(), Matrix4x4::translation(1., 2., 3.));
+    }
+    #[test]
+    fn texture_with_object_transformation() {
+        let s = Object::sphere(
+            Matrix4x4::scaling(2., 2., 2.).inverse(),
+            Material::default(),
+        );
+        let t = Texture::new(TestPattern, Matrix4x4::_IDENTITY);
+        assert_eq!(
+            t.color_at_object(&s, Tuple::point(2., 3., 4.)),
+            Color::new(1., 1.5, 2.)
+        );
+    }
+    #[test]
+    fn texture_with_pattern_transformation() {
+        let s = Object::sphere(Matrix4x4::_IDENTITY, Material::default());
+        let t = Texture::new(TestPattern, Matrix4x4::scaling(2., 2., 2.).inverse());
+        assert_eq!(
+            t.color_at_object(&s, Tuple::point(2., 3., 4.)),
+            Color::new(1., 1.5, 2.)
+        );
+    }
+    #[test]
+    fn texture_with_object_and_pattern_transformation() {
+        let s = Object::sphere(
+            Matrix4x4::scaling(2., 2., 2.).inverse(),
+            Material::default(),
+        );
+        let t = Texture::new(TestPattern, Matrix4x4::translation(0.5, 1., 1.5).inverse());
+        assert_eq!(
+            t.color_at_object(&s, Tuple::point(2.5, 3., 3.5)),
+            Color::new(0.75, 0.5, 0.25)
+        );
+    }
+    #[test]
+    fn texture_in_a_group() {
+        let g = Object::new(
+            Group::new(vec![Object::sphere(
+                Matrix4x4::translation(5., 0., 0.).inverse(),
+                Material::default(),
+            )]),
+            Matrix4x4::scaling(2., 2., 2.).inverse(),
+            Material::default(),
+        );
+        let s = &g.shape_as::<Group>().unwrap().children()[0];
+        let t = Texture::new(TestPattern, Matrix4x4::_IDENTITY);
+        assert_eq!(
+            t.color_at_object(s, Tuple::point(10., 2., 0.)),
+            Color::new(0., 1., 0.)
+        );
+    }
+    #[test]
+    fn gradient_pattern() {
+        let pattern = Gradient::new(WHITE, BLACK);
+        for (point, color) in [
+            (Tuple::point(0., 0., 0.), WHITE),
+            (Tuple::point(0.25, 0., 0.), Color::new(0.75, 0.75, 0.75)),
+            (Tuple::point(0.5, 0., 0.), Color::new(0.5, 0.5, 0.5)),
+            (Tuple::point(0.75, 0., 0.), Color::new(0.25, 0.25, 0.25)),
+        ] {
+            assert_eq!(pattern.local_color_at(point), color);
+        }
+    }
+    #[test]
+    fn ring_pattern() {
+        let pattern = Ring::new(WHITE, BLACK);
+        for (point, color) in [
+            (Tuple::point(0., 0., 0.), WHITE),
+            (Tuple::point(1., 0., 0.), BLACK),
+            (Tuple::point(0., 0., 1.), BLACK),
+            (Tuple::point(0.708, 0., 0.708), BLACK),
+        ] {
+            assert_eq!(pattern.local_color_at(point), color);
+        }
+    }
+    #[test]
+    fn checkers_pattern() {
+        let pattern = Checkers::new(WHITE, BLACK);
+        for (point, color) in [
+            (Tuple::point(0., 0., 0.), WHITE),
+            (Tuple::point(0.99, 0., 0.), WHITE),
+            (Tuple::point(1.01, 0., 0.), BLACK),
+            (Tuple::point(0., 0.99, 0.), WHITE),
+            (Tuple::point(0., 1.01, 0.), BLACK),
+            (Tuple::point(0., 0., 0.99), WHITE),
+            (Tuple::point(0., 0., 1.01), BLACK),
+            (Tuple::point(-0.5, -0.5, 0.5), WHITE),
+        ] {
+            assert_eq!(pattern.local_color_at(point), color);
+        }
+    }
+    #[test]
+    fn refracted_color_with_refracted_ray() {
+        let mut w = World::default_world();
+        w.objects[0].material_mut().ambient = 1.;
+        w.objects[0].material_mut().pattern = Some(Texture::new(TestPattern, Matrix4x4::_IDENTITY));
+        w.objects[1].material_mut().transparency = 1.;
+        w.objects[1].material_mut().refractive_index = 1.5;
+        let r = Ray::new(Tuple::point(0., 0., 0.1), Tuple::vector(0., 1., 0.));
+        let xs = Intersections::new(vec![
+            Intersection::new(-0.9899, &w.objects[0]),
+            Intersection::new(-0.4899, &w.objects[1]),
+            Intersection::new(0.4899, &w.objects[1]),
+            Intersection::new(0.9899, &w.objects[0]),
+        ]);
+        let comps = xs[2].prepare_computations(&r, &xs);
+        assert_eq!(
+            w.refracted_color(&comps, 5),
+            Color::new(0., 0.99888, 0.04722)
+        );
+    }
+    // Composed patterns.
+    const RED: Color = Color::new(1., 0., 0.);
+    const BLUE: Color = Color::new(0., 0., 1.);
+    #[test]
+    fn color_converts_to_solid_texture() {
+        let t = Texture::from(RED);
+        assert_eq!(t.color_at(Tuple::point(0., 0., 0.)), RED);
+        assert_eq!(t.color_at(Tuple::point(-3.5, 2., 7.)), RED);
+        assert_eq!(t.pattern().local_color_at(Tuple::point(1., 2., 3.)), RED);
+        assert_eq!(Solid(BLUE).local_color_at(Tuple::point(1., 2., 3.)), BLUE);
+    }
+    #[test]
+    fn nested_pattern() {
+        let pattern = Checkers::new(
+            Texture::new(Stripe::new(WHITE, BLACK), Matrix4x4::_IDENTITY),
+            Texture::new(
+                Stripe::new(RED, BLUE),
+                Matrix4x4::scaling(0.5, 0.5, 0.5).inverse(),
+            ),
+        );
+        for (point, color) in [
+            (Tuple::point(0.5, 0., 0.5), WHITE),
+            (Tuple::point(-0.5, 0., -0.5), BLACK),
+            (Tuple::point(1.2, 0., 0.5), RED),
+            (Tuple::point(1.5, 0., 0.5), BLUE),
+        ] {
+            assert_eq!(pattern.local_color_at(point), color);
+        }
+    }
+    #[test]
+    fn nested_patterns_nest_arbitrarily() {
+        let stripes = Texture::new(Stripe::new(WHITE, BLACK), Matrix4x4::_IDENTITY);
+        let checkers = Texture::new(
+            Checkers::new(stripes, RED),
+            Matrix4x4::translation(0., 0., 1.).inverse(),
+        );
+        let pattern = Blend::new(checkers, BLUE);
+        // Shifted back a cell along z, this lands in a stripe cell, on the
+        // stripes' second band.
+        assert_eq!(
+            pattern.local_color_at(Tuple::point(1.5, 0., 0.5)),
+            Color::new(0., 0., 0.5)
+        );
+        assert_eq!(
+            pattern.local_color_at(Tuple::point(1.5, 0., 1.5)),
+            Color::new(0.5, 0., 0.5)
+        );
+    }
+    #[test]
+    fn blend_pattern() {
+        let pattern = Blend::new(WHITE, BLACK);
+        assert_eq!(
+            pattern.local_color_at(Tuple::point(0., 0., 0.)),
+            Color::new(0.5, 0.5, 0.5)
+        );
+        let pattern = Blend::new(
+            Texture::new(Stripe::new(RED, BLACK), Matrix4x4::_IDENTITY),
+            Texture::new(
+                Stripe::new(BLUE, BLACK),
+                Matrix4x4::rotation_y(FRAC_PI_2).inverse(),
+            ),
+        );
+        for (point, color) in [
+            (Tuple::point(0.5, 0., -0.5), Color::new(0.5, 0., 0.5)),
+            (Tuple::point(0.5, 0., 0.5), Color::new(0.5, 0., 0.)),
+            (Tuple::point(1.5, 0., -0.5), Color::new(0., 0., 0.5)),
+            (Tuple::point(1.5, 0., 0.5), BLACK),
+        ] {
+            assert_eq!(pattern.local_color_at(point), color);
+        }
+    }
+    #[test]
+    fn radial_gradient_pattern() {
+        let pattern = RadialGradient::new(WHITE, BLACK);
+        for (point, color) in [
+            (Tuple::point(0., 0., 0.), WHITE),
+            (Tuple::point(0.25, 0., 0.), Color::new(0.75, 0.75, 0.75)),
+            (Tuple::point(0., 5., 0.5), Color::new(0.5, 0.5, 0.5)),
+            (Tuple::point(0.6, 0., 0.8), WHITE),
+            (Tuple::point(-1.5, 0., 0.), Color::new(0.5, 0.5, 0.5)),
+        ] {
+            assert_eq!(pattern.local_color_at(point), color);
+        }
+    }
+    #[test]
+    fn perturbed_pattern_jitters_its_input() {
+        let still = Perturbed::new(Texture::new(TestPattern, Matrix4x4::_IDENTITY), 0.);
+        let jittered = Perturbed::new(Texture::new(TestPattern, Matrix4x4::_IDENTITY), 0.1);
+        let mut moved = false;
+        for i in 0..20 {
+            let point = Tuple::point(i as f64 * 0.37, i as f64 * -0.21, 1.3);
+            let plain = Color::new(point.x, point.y, point.z);
+            assert_eq!(still.local_color_at(point), plain);
+            let color = jittered.local_color_at(point);
+            assert!((color.get_red() - point.x).abs() <= 0.15);
+            assert!((color.get_green() - point.y).abs() <= 0.15);
+            assert!((color.get_blue() - point.z).abs() <= 0.15);
+            moved |= color != plain;
+        }
+        assert!(moved);
+    }
+    #[test]
+    fn perturbed_pattern_respects_its_own_transform() {
+        let point = Tuple::point(0.3, 0.6, 0.9);
+        let inner = Texture::new(TestPattern, Matrix4x4::scaling(2., 2., 2.).inverse());
+        let outer = Texture::new(
+            Perturbed::new(inner, 0.),
+            Matrix4x4::translation(1., 0., 0.).inverse(),
+        );
+        assert_eq!(outer.color_at(point), Color::new(-0.35, 0.3, 0.45));
+    }
+    // Noise.
+    #[test]
+    fn perlin_noise_is_zero_on_lattice_points() {
+        for (x, y, z) in [
+            (0., 0., 0.),
+            (1., 2., 3.),
+            (-4., 7., -12.),
+            (255., 256., 257.),
+        ] {
+            assert_eq!(Noise::default().perlin(Tuple::point(x, y, z)), 0.);
+        }
+    }
+    #[test]
+    #[allow(clippy::approx_constant)]
+    fn perlin_noise_matches_reference_implementation() {
+        let n = Noise::default().perlin(Tuple::point(3.14, 42., 7.));
+        assert!((n - 0.13691995878400012).abs() < 1e-12);
+    }
+    #[test]
+    fn perlin_noise_is_bounded_and_smooth() {
+        let noise = Noise::default();
+        for i in 0..1000 {
+            let point = Tuple::point(i as f64 * 0.173, i as f64 * 0.071 - 20., i as f64 * -0.037);
+            let n = noise.perlin(point);
+            assert!((-1.1..=1.1).contains(&n));
+            let nearby = noise.perlin(point + Tuple::vector(1e-4, 1e-4, 1e-4));
+            assert!((n - nearby).abs() < 1e-3);
+        }
+    }
+    /// Points scattered over a few hundred noise cells.
+    fn noise_samples() -> impl Iterator<Item = Tuple> {
+        (0..2000).map(|i| {
+            let i = i as f64;
+            Tuple::point(i * 0.173 - 50., i * 0.071 - 20., i * -0.037 + 9.)
+        })
+    }
+    #[test]
+    fn seeded_noise_is_deterministic() {
+        let (a, b, c) = (Noise::new(42), Noise::new(42), Noise::new(43));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_ne!(a, Noise::default());
+        let mut differs = false;
+        for point in noise_samples() {
+            assert_eq!(a.perlin(point), b.perlin(point));
+            assert_eq!(a.simplex(point), b.simplex(point));
+            differs |= a.perlin(point) != c.perlin(point);
+        }
+        assert!(differs);
+    }
+    #[test]
+    fn seeded_noise_matches_recorded_values() {
+        // Guards the images rendered from seeded noise against accidental
+        // changes to the generator.
+        let noise = Noise::new(2024);
+        let point = Tuple::point(1.37, -2.6, 0.41);
+        assert!((noise.perlin(point) - 0.030092621034949252).abs() < 1e-12);
+        assert!((noise.simplex(point) - 0.043219433709568905).abs() < 1e-12);
+    }
+    #[test]
+    fn simplex_noise_is_bounded_and_smooth() {
+        let noise = Noise::new(7);
+        assert_eq!(noise.simplex(Tuple::point(0., 0., 0.)), 0.);
+        let mut sum = 0.;
+        for point in noise_samples() {
+            let n = noise.simplex(point);
+            assert!((-1.1..=1.1).contains(&n));
+            let nearby = noise.simplex(point + Tuple::vector(1e-4, 1e-4, 1e-4));
+            assert!((n - nearby).abs() < 1e-2);
+            sum += n;
+        }
+        assert!((sum / 2000.).abs() < 0.1);
+        // Simplex cell boundaries fall on a skewed grid; stepping across
+        // them must not jump.
+        for y in 0..100 {
+            for x in 0..100 {
+                let point = Tuple::point(x as f64 / 25., y as f64 / 25., 0.5);
+                let nearby = noise.simplex(point + Tuple::vector(1e-6, 0., 0.));
+                assert!((noise.simplex(point) - nearby).abs() < 1e-4);
+            }
+        }
+    }
+    #[test]
+    fn default_fractal() {
+        let f = Fractal::default();
+        assert_eq!(f.basis, Basis::Perlin);
+        assert_eq!(f.octaves, 6);
+        assert_eq!(f.lacunarity, 2.);
+        assert_eq!(f.gain, 0.5);
+    }
+    #[test]
+    fn single_octave_fbm_is_the_basis_noise() {
+        let noise = Noise::new(1);
+        for basis in [Basis::Perlin, Basis::Simplex] {
+            let fractal = Fractal {
+                basis,
+                octaves: 1,
+                ..Fractal::default()
+            };
+            for point in noise_samples().take(100) {
+                let n = match basis {
+                    Basis::Perlin => noise.perlin(point),
+                    Basis::Simplex => noise.simplex(point),
+                };
+                assert_eq!(noise.fbm(point, &fractal), n);
+                assert_eq!(noise.turbulence(point, &fractal), n.abs().min(1.));
+            }
+        }
+    }
+    #[test]
+    fn fractal_noise_ranges() {
+        let noise = Noise::new(5);
+        for basis in [Basis::Perlin, Basis::Simplex] {
+            let fractal = Fractal {
+                basis,
+                ..Fractal::default()
+            };
+            for point in noise_samples() {
+                assert!((-1.1..=1.1).contains(&noise.fbm(point, &fractal)));
+                assert!((0. ..=1.).contains(&noise.turbulence(point, &fractal)));
+                assert!((0. ..=1.).contains(&noise.ridged(point, &fractal)));
+            }
+        }
+        let none = Fractal {
+            octaves: 0,
+            ..Fractal::default()
+        };
+        let point = Tuple::point(0.5, 0.5, 0.5);
+        assert_eq!(noise.fbm(point, &none), 0.);
+        assert_eq!(noise.turbulence(point, &none), 0.);
+        assert_eq!(noise.ridged(point, &none), 0.);
+    }
+    #[test]
+    fn ridged_noise_peaks_where_noise_is_zero() {
+        let noise = Noise::default();
+        let fractal = Fractal {
+            octaves: 1,
+            ..Fractal::default()
+        };
+        assert_eq!(noise.ridged(Tuple::point(3., -2., 8.), &fractal), 1.);
+    }
+    #[test]
+    fn perturbed_pattern_uses_its_noise() {
+        let point = Tuple::point(0.3, 0.6, 0.9);
+        let mut p = Perturbed::new(Texture::new(TestPattern, Matrix4x4::_IDENTITY), 0.5);
+        let default = p.local_color_at(point);
+        p.noise = Noise::new(99);
+        assert_ne!(p.local_color_at(point), default);
+    }
+    // UV mapping.
+    /// Returns (u, v) as the red and green channels, with `face` as blue.
+    #[derive(Debug)]
+    struct TestUvPattern(f64);
+    impl UvPattern for TestUvPattern {
+        fn uv_color_at(&self, u: f64, v: f64) -> Color {
+            Color::new(u, v, self.0)
+        }
+    }
+    fn assert_uv((u, v): (f64, f64), (eu, ev): (f64, f64)) {
+        assert!(
+            (u - eu).abs() < EPSILON && (v - ev).abs() < EPSILON,
+            "({u}, {v}) != ({eu}, {ev})"
+        );
+    }
+    #[test]
+    fn uv_checkers_pattern() {
+        let pattern = UvCheckers::new(2., 2., BLACK, WHITE);
+        for (u, v, color) in [
+            (0., 0., BLACK),
+            (0.5, 0., WHITE),
+            (0., 0.5, WHITE),
+            (0.5, 0.5, BLACK),
+            (1., 1., BLACK),
+        ] {
+            assert_eq!(pattern.uv_color_at(u, v), color);
+        }
+    }
+    #[test]
+    fn spherical_mapping() {
+        for (point, uv) in [
+            (Tuple::point(0., 0., -1.), (0., 0.5)),
+            (Tuple::point(1., 0., 0.), (0.25, 0.5)),
+            (Tuple::point(0., 0., 1.), (0.5, 0.5)),
+            (Tuple::point(-1., 0., 0.), (0.75, 0.5)),
+            (Tuple::point(0., 1., 0.), (0.5, 1.)),
+            (Tuple::point(0., -1., 0.), (0.5, 0.)),
+            (Tuple::point(FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.), (0.25, 0.75)),
+        ] {
+            assert_uv(spherical_map(point), uv);
+        }
+    }
+    #[test]
+    fn texture_map_with_spherical_mapping() {
+        let pattern = TextureMap::new(UvMapping::Spherical, UvCheckers::new(16., 8., BLACK, WHITE));
+        for (point, color) in [
+            (Tuple::point(0.4315, 0.4670, 0.7719), WHITE),
+            (Tuple::point(-0.9654, 0.2552, -0.0534), BLACK),
+            (Tuple::point(0.1039, 0.7090, 0.6975), WHITE),
+            (Tuple::point(-0.4986, -0.7856, -0.3663), BLACK),
+            (Tuple::point(-0.0317, -0.9395, 0.3411), BLACK),
+            (Tuple::point(0.4809, -0.7721, 0.4154), BLACK),
+            (Tuple::point(0.0285, -0.9612, -0.2747), BLACK),
+            (Tuple::point(-0.5734, -0.2162, -0.7903), WHITE),
+            (Tuple::point(0.7688, -0.1470, 0.6223), BLACK),
+            (Tuple::point(-0.7652, 0.2175, 0.6060), BLACK),
+        ] {
+            assert_eq!(pattern.local_color_at(point), color);
+        }
+    }
+    #[test]
+    fn planar_mapping() {
+        for (point, uv) in [
+            (Tuple::point(0.25, 0., 0.5), (0.25, 0.5)),
+            (Tuple::point(0.25, 0., -0.25), (0.25, 0.75)),
+            (Tuple::point(0.25, 0.5, -0.25), (0.25, 0.75)),
+            (Tuple::point(1.25, 0., 0.5), (0.25, 0.5)),
+            (Tuple::point(0.25, 0., -1.75), (0.25, 0.25)),
+            (Tuple::point(1., 0., -1.), (0., 0.)),
+            (Tuple::point(0., 0., 0.), (0., 0.)),
+        ] {
+            assert_uv(planar_map(point), uv);
+        }
+    }
+    #[test]
+    fn cylindrical_mapping() {
+        for (point, uv) in [
+            (Tuple::point(0., 0., -1.), (0., 0.)),
+            (Tuple::point(0., 0.5, -1.), (0., 0.5)),
+            (Tuple::point(0., 1., -1.), (0., 0.)),
+            (
+                Tuple::point(FRAC_1_SQRT_2, 0.5, -FRAC_1_SQRT_2),
+                (0.125, 0.5),
+            ),
+            (Tuple::point(1., 0.5, 0.), (0.25, 0.5)),
+            (
+                Tuple::point(FRAC_1_SQRT_2, 0.5, FRAC_1_SQRT_2),
+                (0.375, 0.5),
+            ),
+            (Tuple::point(0., -0.25, 1.), (0.5, 0.75)),
+            (
+                Tuple::point(-FRAC_1_SQRT_2, 0.5, FRAC_1_SQRT_2),
+                (0.625, 0.5),
+            ),
+            (Tuple::point(-1., 1.25, 0.), (0.75, 0.25)),
+            (
+                Tuple::point(-FRAC_1_SQRT_2, 0.5, -FRAC_1_SQRT_2),
+                (0.875, 0.5),
+            ),
+        ] {
+            assert_uv(cylindrical_map(point), uv);
+        }
+        let pattern = TextureMap::new(UvMapping::Cylindrical, TestUvPattern(0.));
+        assert_eq!(
+            pattern.local_color_at(Tuple::point(1., 0.5, 0.)),
+            Color::new(0.25, 0.5, 0.)
+        );
+    }
+    #[test]
+    fn cube_face_from_point() {
+        for (point, face) in [
+            (Tuple::point(-1., 0.5, -0.25), CubeFace::Left),
+            (Tuple::point(1.1, -0.75, 0.8), CubeFace::Right),
+            (Tuple::point(0.1, 0.6, 0.9), CubeFace::Front),
+            (Tuple::point(-0.7, 0., -2.), CubeFace::Back),
+            (Tuple::point(0.5, 1., 0.9), CubeFace::Up),
+            (Tuple::point(-0.2, -1.3, 1.1), CubeFace::Down),
+        ] {
+            assert_eq!(CubeFace::from_point(point), face);
+        }
+    }
+    #[test]
+    fn cube_mapping() {
+        for (point, face, uv) in [
+            (Tuple::point(-0.5, 0.5, 1.), CubeFace::Front, (0.25, 0.75)),
+            (Tuple::point(0.5, -0.5, 1.), CubeFace::Front, (0.75, 0.25)),
+            (Tuple::point(0.5, 0.5, -1.), CubeFace::Back, (0.25, 0.75)),
+            (Tuple::point(-0.5, -0.5, -1.), CubeFace::Back, (0.75, 0.25)),
+            (Tuple::point(-1., 0.5, -0.5), CubeFace::Left, (0.25, 0.75)),
+            (Tuple::point(-1., -0.5, 0.5), CubeFace::Left, (0.75, 0.25)),
+            (Tuple::point(1., 0.5, 0.5), CubeFace::Right, (0.25, 0.75)),
+            (Tuple::point(1., -0.5, -0.5), CubeFace::Right, (0.75, 0.25)),
+            (Tuple::point(-0.5, 1., -0.5), CubeFace::Up, (0.25, 0.75)),
+            (Tuple::point(0.5, 1., 0.5), CubeFace::Up, (0.75, 0.25)),
+            (Tuple::point(-0.5, -1., 0.5), CubeFace::Down, (0.25, 0.75)),
+            (Tuple::point(0.5, -1., -0.5), CubeFace::Down, (0.75, 0.25)),
+        ] {
+            let (f, u, v) = cube_map(point);
+            assert_eq!(f, face);
+            assert_uv((u, v), uv);
+        }
+    }
+    #[test]
+    fn texture_map_with_cube_mapping() {
+        let pattern = TextureMap::new(UvMapping::Cube, TestUvPattern(0.));
+        assert_eq!(
+            pattern.local_color_at(Tuple::point(1., -0.5, -0.5)),
+            Color::new(0.75, 0.25, 0.)
+        );
+    }
+    #[test]
+    fn cube_map_picks_pattern_by_face() {
+        let faces: [Rc<dyn UvPattern>; 6] = [
+            Rc::new(TestUvPattern(0.)),
+            Rc::new(TestUvPattern(0.2)),
+            Rc::new(TestUvPattern(0.4)),
+            Rc::new(TestUvPattern(0.6)),
+            Rc::new(TestUvPattern(0.8)),
+            Rc::new(TestUvPattern(1.)),
+        ];
+        let pattern = CubeMap::new(faces);
+        for (point, color) in [
+            (Tuple::point(-1., 0.5, -0.5), Color::new(0.25, 0.75, 0.)),
+            (Tuple::point(-0.5, 0.5, 1.), Color::new(0.25, 0.75, 0.2)),
+            (Tuple::point(1., 0.5, 0.5), Color::new(0.25, 0.75, 0.4)),
+            (Tuple::point(0.5, 0.5, -1.), Color::new(0.25, 0.75, 0.6)),
+            (Tuple::point(-0.5, 1., -0.5), Color::new(0.25, 0.75, 0.8)),
+            (Tuple::point(-0.5, -1., 0.5), Color::new(0.25, 0.75, 1.)),
+        ] {
+            assert_eq!(pattern.local_color_at(point), color);
+        }
+    }
+    /// A 2x2 image: red and green along the top, blue and white below.
+    fn two_by_two() -> Canvas {
+        let mut c = Canvas::new(2, 2);
+        c.set_pixel(0, 0, RED);
+        c.set_pixel(1, 0, Color::new(0., 1., 0.));
+        c.set_pixel(0, 1, BLUE);
+        c.set_pixel(1, 1, WHITE);
+        c
+    }
+    #[test]
+    fn image_texture_nearest() {
+        let image = ImageTexture::new(two_by_two(), Filter::Nearest, Wrap::Repeat);
+        assert_eq!(image.canvas().get_width(), 2);
+        for (u, v, color) in [
+            (0.25, 0.75, RED),
+            (0.75, 0.75, Color::new(0., 1., 0.)),
+            (0.25, 0.25, BLUE),
+            (0.75, 0.25, WHITE),
+            (0.01, 0.99, RED),
+            (0.49, 0.51, RED),
+        ] {
+            assert_eq!(image.uv_color_at(u, v), color);
+        }
+    }
+    #[test]
+    fn image_texture_wrap_modes() {
+        let repeat = ImageTexture::new(two_by_two(), Filter::Nearest, Wrap::Repeat);
+        let clamp = ImageTexture::new(two_by_two(), Filter::Nearest, Wrap::Clamp);
+        assert_eq!(repeat.uv_color_at(1.25, 0.75), RED);
+        assert_eq!(clamp.uv_color_at(1.25, 0.75), Color::new(0., 1., 0.));
+        assert_eq!(repeat.uv_color_at(-0.25, 0.75), Color::new(0., 1., 0.));
+        assert_eq!(clamp.uv_color_at(-0.25, 0.75), RED);
+        assert_eq!(repeat.uv_color_at(0.25, 1.25), BLUE);
+        assert_eq!(clamp.uv_color_at(0.25, 1.25), RED);
+        assert_eq!(clamp.uv_color_at(1., 0.), WHITE);
+    }
+    #[test]
+    fn image_texture_bilinear() {
+        let repeat = ImageTexture::new(two_by_two(), Filter::Bilinear, Wrap::Repeat);
+        let clamp = ImageTexture::new(two_by_two(), Filter::Bilinear, Wrap::Clamp);
+        // Pixel centers sample exactly one pixel.
+        assert_eq!(repeat.uv_color_at(0.25, 0.75), RED);
+        assert_eq!(repeat.uv_color_at(0.75, 0.25), WHITE);
+        // The middle of the image is an even blend of all four.
+        assert_eq!(repeat.uv_color_at(0.5, 0.5), Color::new(0.5, 0.5, 0.5));
+        // Halfway between red and green.
+        assert_eq!(clamp.uv_color_at(0.5, 0.75), Color::new(0.5, 0.5, 0.));
+        // At the left edge, clamping holds the edge pixel while repeating
+        // blends in the opposite side.
+        assert_eq!(clamp.uv_color_at(0., 0.75), RED);
+        assert_eq!(repeat.uv_color_at(0., 0.75), Color::new(0.5, 0.5, 0.));
+    }
+    #[test]
+    fn image_texture_on_a_sphere() {
+        let s = Object::sphere(
+            Matrix4x4::scaling(2., 2., 2.).inverse(),
+            Material::default(),
+        );
+        let t = Texture::new(
+            TextureMap::new(
+                UvMapping::Spherical,
+                ImageTexture::new(two_by_two(), Filter::Nearest, Wrap::Repeat),
+            ),
+            Matrix4x4::_IDENTITY,
+        );
+        // u = 0.25 and v is above the equator: the top-left pixel.
+        assert_eq!(t.color_at_object(&s, Tuple::point(SQRT_2, SQRT_2, 0.)), RED);
+        // u = 0.75 and below the equator: the bottom-right pixel.
+        assert_eq!(
+            t.color_at_object(&s, Tuple::point(-SQRT_2, -SQRT_2, 0.)),
+            WHITE
+        );
+    }
+    #[test]
+    fn image_texture_from_a_file_on_a_sphere() {
+        let image = Canvas::read_ppm("tests/fixtures/uv_grid.ppm").unwrap();
+        let s = Object::sphere(
+            Matrix4x4::_IDENTITY,
+            Material {
+                pattern: Some(Texture::new(
+                    TextureMap::new(
+                        UvMapping::Spherical,
+                        ImageTexture::new(image, Filter::Nearest, Wrap::Repeat),
+                    ),
+                    Matrix4x4::_IDENTITY,
+                )),
+                ..Material::default()
+            },
+        );
+        let pattern = s.material().pattern.as_ref().unwrap();
+        // The middle of each texel's quarter of longitude, in the northern
+        // hemisphere for the top row and the southern for the bottom one.
+        let longitudes = [(0.5, -0.5), (0.5, 0.5), (-0.5, 0.5), (-0.5, -0.5)];
+        for (y, (row, height)) in UV_GRID
+            .iter()
+            .zip([FRAC_1_SQRT_2, -FRAC_1_SQRT_2])
+            .enumerate()
+        {
+            for (x, (&color, (px, pz))) in row.iter().zip(longitudes).enumerate() {
+                let point = Tuple::point(px, height, pz);
+                assert_eq!(
+                    pattern.color_at_object(&s, point),
+                    color,
+                    "texel ({}, {})",
+                    x,
+                    y
+                );
+            }
+        }
+    }
+    // Color tests.
+    #[test]
+    fn add_colors() {
+        assert_eq!(
+            Color::new(0.9, 0.6, 0.75) + Color::new(0.7, 0.1, 0.25),
+            Color::new(1.6, 0.7, 1.0)
+        )
+    }
+    #[test]
+    fn sub_colors() {
+        assert_eq!(
+            Color::new(0.9, 0.6, 0.75) - Color::new(0.7, 0.1, 0.25),
+            Color::new(0.2, 0.5, 0.5)
+        )
+    }
+    #[test]
+    fn scale_color() {
+        assert_eq!(Color::new(0.2, 0.3, 0.4) * 2.0, Color::new(0.4, 0.6, 0.8))
+    }
+    #[test]
+    fn mul_colors() {
+        assert_eq!(
+            Color::new(1.0, 0.2, 0.4) * Color::new(0.9, 1.0, 0.1),
+            Color::new(0.9, 0.2, 0.04)
+        )
+    }
+    // Canvas tests.
+    #[test]
+    fn set_pixel_canvas() {
+        let mut c = Canvas::new(10, 20);
+        let red = Color::new(1.0, 0.0, 0.0);
+        c.set_pixel(2, 3, red);
+        assert_eq!(c.pixel_at(2, 3), red);
+    }
+    #[test]
+    fn write_blank_canvas() {
+        let c = Canvas::new(10, 20);
+        c.write_ppm("images/blank.ppm");
+    }
+    #[test]
+    fn ppm_wrong_magic_number() {
+        let ppm = b"P32\n1 1\n255\n0 0 0\n";
+        assert!(matches!(Canvas::parse_ppm(ppm), Err(PpmError::Header(_))));
+    }
+    #[test]
+    fn ppm_read_size() {
+        let c = Canvas::parse_ppm(b"P3\n10 2\n255\n0 0 0 ");
+        assert!(matches!(
+            c,
+            Err(PpmError::Truncated {
+                expected: 60,
+                found: 3
+            })
+        ));
+
+        let mut ppm = b"P3\n10 2\n255\n".to_vec();
+        ppm.extend(b"0 0 0\n".repeat(20));
+        let c = Canvas::parse_ppm(&ppm).unwrap();
+        assert_eq!(c.get_width(), 10);
+        assert_eq!(c.get_height(), 2);
+    }
+    #[test]
+    fn ppm_read_pixel_data() {
+        let ppm = b"P3\n4 3\n255\n\
+255 127 0  0 127 255  127 255 0  255 255 255\n\
+0 0 0  255 0 0  0 255 0  0 0 255\n\
+255 255 0  0 255 255  255 0 255  127 127 127\n";
+        let c = Canvas::parse_ppm(ppm).unwrap();
+        let expected = [
+            (0, 0, Color::new(1.0, 0.49804, 0.0)),
+            (1, 0, Color::new(0.0, 0.49804, 1.0)),
+            (2, 0, Color::new(0.49804, 1.0, 0.0)),
+            (3, 0, Color::new(1.0, 1.0, 1.0)),
+            (0, 1, Color::new(0.0, 0.0, 0.0)),
+            (1, 1, Color::new(1.0, 0.0, 0.0)),
+            (2, 1, Color::new(0.0, 1.0, 0.0)),
+            (3, 1, Color::new(0.0, 0.0, 1.0)),
+            (0, 2, Color::new(1.0, 1.0, 0.0)),
+            (1, 2, Color::new(0.0, 1.0, 1.0)),
+            (2, 2, Color::new(1.0, 0.0, 1.0)),
+            (3, 2, Color::new(0.49804, 0.49804, 0.49804)),
+        ];
+        for (x, y, color) in expected {
+            assert_eq!(c.pixel_at(x, y), color);
+        }
+    }
+    #[test]
+    fn ppm_ignores_comments() {
+        let ppm = b"P3\n# this is a comment\n2 1\n# this, too\n255\n\
+# another comment\n255 255 255\n# oh, no, comments in the pixel data!\n255 0 255\n";
+        let c = Canvas::parse_ppm(ppm).unwrap();
+        assert_eq!(c.pixel_at(0, 0), Color::new(1.0, 1.0, 1.0));
+        assert_eq!(c.pixel_at(1, 0), Color::new(1.0, 0.0, 1.0));
+    }
+    #[test]
+    fn ppm_samples_span_lines() {
+        let ppm = b"P3\n1 1\n255\n51\n153\n\n204\n";
+        let c = Canvas::parse_ppm(ppm).unwrap();
+        assert_eq!(c.pixel_at(0, 0), Color::new(0.2, 0.6, 0.8));
+    }
+    #[test]
+    fn ppm_scaled_by_max_value() {
+        let ppm = b"P3\n2 2\n100\n100 100 100  50 50 50\n75 50 25  0 0 0\n";
+        let c = Canvas::parse_ppm(ppm).unwrap();
+        assert_eq!(c.pixel_at(0, 1), Color::new(0.75, 0.5, 0.25));
+    }
+    #[test]
+    fn ppm_read_binary() {
+        let mut ppm = b"P6 # raw\n2 1\n255\n".to_vec();
+        ppm.extend([255, 0, 51, 0, 10, 255]);
+        let c = Canvas::parse_ppm(&ppm).unwrap();
+        assert_eq!(c.pixel_at(0, 0), Color::new(1.0, 0.0, 0.2));
+        assert_eq!(c.pixel_at(1, 0), Color::new(0.0, 10.0 / 255.0, 1.0));
+
+        // Samples above 255 take two big-endian bytes.
+        let mut ppm = b"P6\n1 1\n1000\n".to_vec();
+        ppm.extend([0x03, 0xe8, 0x01, 0xf4, 0x00, 0x00]);
+        let c = Canvas::parse_ppm(&ppm).unwrap();
+        assert_eq!(c.pixel_at(0, 0), Color::new(1.0, 0.5, 0.0));
+
+        // A byte that looks like whitespace right after the header is data.
+        let mut ppm = b"P6\n1 1\n255\n".to_vec();
+        ppm.extend([b' ', b'\n', b'#']);
+        let c = Canvas::parse_ppm(&ppm).unwrap();
+        assert_eq!(c.pixel_at(0, 0).get_red(), 32.0 / 255.0);
+    }
+    #[test]
+    fn ppm_malformed() {
+        let header = |ppm: &[u8]| matches!(Canvas::parse_ppm(ppm), Err(PpmError::Header(_)));
+        assert!(header(b""));
+        assert!(header(b"P3\n"));
+        assert!(header(b"P3\n2 x\n255\n"));
+        assert!(header(b"P3\n0 1\n255\n"));
+        assert!(header(b"P3\n1 1\n0\n0 0 0"));
+        assert!(header(b"P3\n1 1\n65536\n0 0 0"));
+        assert!(header(b"P6\n1 1\n255"));
+        assert!(header(b"P3\n4294967296 4294967296\n255\n0 0 0"));
+
+        assert!(matches!(
+            Canvas::parse_ppm(b"P3\n2 1\n255\n0 0 0 0 zero 0"),
+            Err(PpmError::Pixel { x: 1, y: 0, .. })
+        ));
+        assert!(matches!(
+            Canvas::parse_ppm(b"P3\n1 2\n100\n0 0 0 0 101 0"),
+            Err(PpmError::Pixel { x: 0, y: 1, .. })
+        ));
+        assert!(matches!(
+            Canvas::parse_ppm(b"P6\n2 1\n255\n\x01\x02\x03\x04"),
+            Err(PpmError::Truncated {
+                expected: 6,
+                found: 4
+            })
+        ));
+        assert!(matches!(
+            Canvas::parse_ppm(b"P6\n1 1\n256\n\x01\x02\x03\x04\x05"),
+            Err(PpmError::Truncated {
+                expected: 3,
+                found: 2
+            })
+        ));
+        assert!(matches!(
+            Canvas::parse_ppm(b"P3\n100000 100000\n255\n0 0 0\n"),
+            Err(PpmError::Truncated {
+                expected: 30_000_000_000,
+                found: 3
+            })
+        ));
+        assert!(matches!(
+            Canvas::read_ppm("images/missing.ppm"),
+            Err(PpmError::Io(_))
+        ));
+    }
+    #[test]
+    fn ppm_round_trip() {
+        let mut c = Canvas::new(5, 3);
+        c.set_pixel(0, 0, Color::new(1.5, 0.0, 0.0));
+        c.set_pixel(2, 1, Color::new(0.0, 0.5, 0.0));
+        c.set_pixel(4, 2, Color::new(-0.5, 0.0, 1.0));
+        // Unique per process, so concurrent runs of the suite don't race.
+        let path = std::env::temp_dir().join(format!("ppm_round_trip_{}.ppm", std::process::id()));
+        let path = path.to_str().unwrap();
+        c.write_ppm(path);
+        let read = Canvas::read_ppm(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(read.get_width(), 5);
+        assert_eq!(read.get_height(), 3);
+        assert_eq!(read.pixel_at(0, 0), Color::new(1.0, 0.0, 0.0));
+        assert!((read.pixel_at(2, 1).get_green() - 0.5).abs() < 1.0 / 255.0);
+        assert_eq!(read.pixel_at(4, 2), Color::new(0.0, 0.0, 1.0));
+        assert_eq!(read.pixel_at(1, 1), Color::new(0.0, 0.0, 0.0));
+    }
+    /// The pixels of `tests/fixtures/uv_grid.ppm` and its raw twin.
+    const UV_GRID: [[Color; 4]; 2] = [
+        [
+            Color::new(1., 0., 0.),
+            Color::new(1., 1., 0.),
+            Color::new(0., 1., 0.),
+            Color::new(0., 1., 1.),
+        ],
+        [
+            Color::new(0., 0., 1.),
+            Color::new(1., 0., 1.),
+            Color::new(1., 1., 1.),
+            Color::new(0., 0., 0.),
+        ],
+    ];
+    #[test]
+    fn ppm_read_fixtures() {
+        for path in [
+            "tests/fixtures/uv_grid.ppm",
+            "tests/fixtures/uv_grid_raw.ppm",
+        ] {
+            let c = Canvas::read_ppm(path).unwrap();
+            assert_eq!(c.get_width(), 4);
+            assert_eq!(c.get_height(), 2);
+            for (y, row) in UV_GRID.iter().enumerate() {
+                for (x, &color) in row.iter().enumerate() {
+                    assert_eq!(c.pixel_at(x, y), color, "{} at ({}, {})", path, x, y);
+                }
+            }
+        }
     }
 }
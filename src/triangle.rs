@@ -0,0 +1,130 @@
+use crate::{
+    bounds::BoundingBox,
+    intersection::{Intersection, Intersections, EPSILON},
+    ray::Ray,
+    shapes::{Object, Shape},
+    tuple::Tuple,
+};
+
+/// A flat triangle, with its edges and face normal precomputed.
+#[derive(Debug)]
+pub struct Triangle {
+    pub p1: Tuple,
+    pub p2: Tuple,
+    pub p3: Tuple,
+    pub e1: Tuple,
+    pub e2: Tuple,
+    pub normal: Tuple,
+}
+
+impl Triangle {
+    pub fn new(p1: Tuple, p2: Tuple, p3: Tuple) -> Self {
+        let e1 = p2 - p1;
+        let e2 = p3 - p1;
+        Self {
+            p1,
+            p2,
+            p3,
+            e1,
+            e2,
+            normal: Tuple::cross(e2, e1).normalize(),
+        }
+    }
+}
+
+/// Möller–Trumbore: the `t` of the hit along with its barycentric `u` and
+/// `v`, or `None` when the ray misses.
+fn intersect_triangle(p1: Tuple, e1: Tuple, e2: Tuple, ray: &Ray) -> Option<(f64, f64, f64)> {
+    let dir_cross_e2 = Tuple::cross(ray.direction, e2);
+    let det = Tuple::dot(e1, dir_cross_e2);
+    // The ray is parallel to the triangle's plane. `det` scales with both
+    // edges and the ray direction, so the cutoff has to as well.
+    if det.abs() <= EPSILON * e1.length() * e2.length() * ray.direction.length() {
+        return None;
+    }
+
+    let f = 1. / det;
+    let p1_to_origin = ray.origin - p1;
+    let u = f * Tuple::dot(p1_to_origin, dir_cross_e2);
+    if !(0. ..=1.).contains(&u) {
+        return None;
+    }
+
+    let origin_cross_e1 = Tuple::cross(p1_to_origin, e1);
+    let v = f * Tuple::dot(ray.direction, origin_cross_e1);
+    if v < 0. || u + v > 1. {
+        return None;
+    }
+
+    Some((f * Tuple::dot(e2, origin_cross_e1), u, v))
+}
+
+impl Shape for Triangle {
+    fn local_intersect<'a>(&'a self, ray: &Ray, object: &'a Object) -> Intersections<'a> {
+        match intersect_triangle(self.p1, self.e1, self.e2, ray) {
+            Some((t, u, v)) => Intersections::new(vec![Intersection::with_uv(t, object, u, v)]),
+            None => Intersections::default(),
+        }
+    }
+    fn local_normal_at(&self, _point: Tuple) -> Tuple {
+        self.normal
+    }
+    fn bounds(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::default();
+        bounds.add_point(self.p1);
+        bounds.add_point(self.p2);
+        bounds.add_point(self.p3);
+        bounds
+    }
+}
+
+/// A triangle whose normal is interpolated from the normals at its vertices.
+#[derive(Debug)]
+pub struct SmoothTriangle {
+    pub p1: Tuple,
+    pub p2: Tuple,
+    pub p3: Tuple,
+    pub n1: Tuple,
+    pub n2: Tuple,
+    pub n3: Tuple,
+    pub e1: Tuple,
+    pub e2: Tuple,
+}
+
+impl SmoothTriangle {
+    pub fn new(p1: Tuple, p2: Tuple, p3: Tuple, n1: Tuple, n2: Tuple, n3: Tuple) -> Self {
+        Self {
+            p1,
+            p2,
+            p3,
+            n1,
+            n2,
+            n3,
+            e1: p2 - p1,
+            e2: p3 - p1,
+        }
+    }
+}
+
+impl Shape for SmoothTriangle {
+    fn local_intersect<'a>(&'a self, ray: &Ray, object: &'a Object) -> Intersections<'a> {
+        match intersect_triangle(self.p1, self.e1, self.e2, ray) {
+            Some((t, u, v)) => Intersections::new(vec![Intersection::with_uv(t, object, u, v)]),
+            None => Intersections::default(),
+        }
+    }
+    /// Without a hit to interpolate from, fall back to the face normal.
+    fn local_normal_at(&self, _point: Tuple) -> Tuple {
+        Tuple::cross(self.e2, self.e1).normalize()
+    }
+    fn local_normal_at_hit(&self, _point: Tuple, hit: &Intersection) -> Tuple {
+        self.n2 * hit.u() + self.n3 * hit.v() + self.n1 * (1. - hit.u() - hit.v())
+    }
+    fn bounds(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::default();
+        bounds.add_point(self.p1);
+        bounds.add_point(self.p2);
+        bounds.add_point(self.p3);
+        bounds
+    }
+}
@@ -9,6 +9,7 @@ pub struct Tuple {
 }
 #[allow(dead_code)]
 impl Tuple {
+    #[allow(clippy::self_named_constructors)]
     pub fn tuple(x: f64, y: f64, z: f64, w: f64) -> Tuple {
         Tuple { x, y, z, w }
     }
@@ -35,6 +36,9 @@ impl Tuple {
             a.x * b.y - a.y * b.x,
         )
     }
+    pub fn reflect(incoming: Self, normal: Self) -> Self {
+        incoming - normal * 2. * Self::dot(incoming, normal)
+    }
 }
 impl Neg for Tuple {
     type Output = Tuple;
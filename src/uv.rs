@@ -0,0 +1,252 @@
+use std::{f64::consts::PI, fmt::Debug, rc::Rc};
+
+use crate::{canvas::Canvas, color::Color, pattern::Pattern, tuple::Tuple};
+
+// Mappings from a point on a shape to (u, v) coordinates, each in 0..1.
+// They expect points in the unit shapes' object space.
+
+/// Longitude and latitude on a unit sphere, with v = 1 at the north pole.
+pub fn spherical_map(point: Tuple) -> (f64, f64) {
+    let theta = point.x.atan2(point.z);
+    let radius = Tuple::vector(point.x, point.y, point.z).length();
+    let phi = (point.y / radius).acos();
+    // atan2 gives -pi..pi, and u should increase counterclockwise seen from
+    // above.
+    let raw_u = theta / (2. * PI);
+    (1. - (raw_u + 0.5), 1. - phi / PI)
+}
+
+/// The xz plane, repeating every unit.
+pub fn planar_map(point: Tuple) -> (f64, f64) {
+    (point.x.rem_euclid(1.), point.z.rem_euclid(1.))
+}
+
+/// Around the y axis, with v repeating every unit of height.
+pub fn cylindrical_map(point: Tuple) -> (f64, f64) {
+    let theta = point.x.atan2(point.z);
+    let raw_u = theta / (2. * PI);
+    (1. - (raw_u + 0.5), point.y.rem_euclid(1.))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CubeFace {
+    Left,
+    Right,
+    Front,
+    Back,
+    Up,
+    Down,
+}
+
+impl CubeFace {
+    /// The face of the -1..1 cube that `point` lies on: the one its largest
+    /// component points at.
+    pub fn from_point(point: Tuple) -> Self {
+        let coord = point.x.abs().max(point.y.abs()).max(point.z.abs());
+        if coord == point.x {
+            CubeFace::Right
+        } else if coord == -point.x {
+            CubeFace::Left
+        } else if coord == point.y {
+            CubeFace::Up
+        } else if coord == -point.y {
+            CubeFace::Down
+        } else if coord == point.z {
+            CubeFace::Front
+        } else {
+            CubeFace::Back
+        }
+    }
+}
+
+/// The face of the -1..1 cube that `point` is on, and where on that face,
+/// with each face seen from outside the cube.
+pub fn cube_map(point: Tuple) -> (CubeFace, f64, f64) {
+    let half = |n: f64| n.rem_euclid(2.) / 2.;
+    let face = CubeFace::from_point(point);
+    let (u, v) = match face {
+        CubeFace::Front => (half(point.x + 1.), half(point.y + 1.)),
+        CubeFace::Back => (half(1. - point.x), half(point.y + 1.)),
+        CubeFace::Left => (half(point.z + 1.), half(point.y + 1.)),
+        CubeFace::Right => (half(1. - point.z), half(point.y + 1.)),
+        CubeFace::Up => (half(point.x + 1.), half(1. - point.z)),
+        CubeFace::Down => (half(point.x + 1.), half(point.z + 1.)),
+    };
+    (face, u, v)
+}
+
+/// A color that varies over a two-dimensional (u, v) surface.
+pub trait UvPattern: Debug {
+    fn uv_color_at(&self, u: f64, v: f64) -> Color;
+}
+
+/// `width` by `height` checkers over the unit square.
+#[derive(Debug)]
+pub struct UvCheckers {
+    pub width: f64,
+    pub height: f64,
+    pub a: Color,
+    pub b: Color,
+}
+
+impl UvCheckers {
+    pub fn new(width: f64, height: f64, a: Color, b: Color) -> Self {
+        Self {
+            width,
+            height,
+            a,
+            b,
+        }
+    }
+}
+
+impl UvPattern for UvCheckers {
+    fn uv_color_at(&self, u: f64, v: f64) -> Color {
+        let sum = (u * self.width).floor() + (v * self.height).floor();
+        if sum.rem_euclid(2.) == 0. {
+            self.a
+        } else {
+            self.b
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Filter {
+    /// The color of the closest pixel.
+    Nearest,
+    /// A blend of the four closest pixels, weighted by distance.
+    Bilinear,
+}
+
+/// What happens to (u, v) coordinates outside 0..1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Wrap {
+    /// The image tiles.
+    Repeat,
+    /// The edge pixels stretch outwards.
+    Clamp,
+}
+
+/// An image stretched over the unit square, with v = 0 along the bottom
+/// row of the canvas.
+#[derive(Clone, Debug)]
+pub struct ImageTexture {
+    canvas: Canvas,
+    pub filter: Filter,
+    pub wrap: Wrap,
+}
+
+impl ImageTexture {
+    pub fn new(canvas: Canvas, filter: Filter, wrap: Wrap) -> Self {
+        Self {
+            canvas,
+            filter,
+            wrap,
+        }
+    }
+    pub fn canvas(&self) -> &Canvas {
+        &self.canvas
+    }
+    /// The pixel at a column and row that may lie outside the image.
+    fn texel(&self, x: i64, y: i64) -> Color {
+        let (width, height) = (
+            self.canvas.get_width() as i64,
+            self.canvas.get_height() as i64,
+        );
+        let (x, y) = match self.wrap {
+            Wrap::Repeat => (x.rem_euclid(width), y.rem_euclid(height)),
+            Wrap::Clamp => (x.clamp(0, width - 1), y.clamp(0, height - 1)),
+        };
+        self.canvas.pixel_at(x as usize, y as usize)
+    }
+}
+
+impl UvPattern for ImageTexture {
+    fn uv_color_at(&self, u: f64, v: f64) -> Color {
+        // Pixel coordinates, measured so that pixel centers sit on the
+        // half-integers.
+        let x = u * self.canvas.get_width() as f64;
+        let y = (1. - v) * self.canvas.get_height() as f64;
+        match self.filter {
+            Filter::Nearest => self.texel(x.floor() as i64, y.floor() as i64),
+            Filter::Bilinear => {
+                let (x, y) = (x - 0.5, y - 0.5);
+                let (x0, y0) = (x.floor(), y.floor());
+                let (tx, ty) = (x - x0, y - y0);
+                let (x0, y0) = (x0 as i64, y0 as i64);
+                let top = self.texel(x0, y0) * (1. - tx) + self.texel(x0 + 1, y0) * tx;
+                let bottom = self.texel(x0, y0 + 1) * (1. - tx) + self.texel(x0 + 1, y0 + 1) * tx;
+                top * (1. - ty) + bottom * ty
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UvMapping {
+    Spherical,
+    Planar,
+    Cylindrical,
+    /// Every face of the cube shows the whole pattern.
+    Cube,
+}
+
+/// Wraps a `UvPattern` around a shape, turning it into a `Pattern`.
+#[derive(Clone, Debug)]
+pub struct TextureMap {
+    pub mapping: UvMapping,
+    pattern: Rc<dyn UvPattern>,
+}
+
+impl TextureMap {
+    pub fn new(mapping: UvMapping, pattern: impl UvPattern + 'static) -> Self {
+        Self {
+            mapping,
+            pattern: Rc::new(pattern),
+        }
+    }
+}
+
+impl Pattern for TextureMap {
+    fn local_color_at(&self, point: Tuple) -> Color {
+        let (u, v) = match self.mapping {
+            UvMapping::Spherical => spherical_map(point),
+            UvMapping::Planar => planar_map(point),
+            UvMapping::Cylindrical => cylindrical_map(point),
+            UvMapping::Cube => {
+                let (_, u, v) = cube_map(point);
+                (u, v)
+            }
+        };
+        self.pattern.uv_color_at(u, v)
+    }
+}
+
+/// A different `UvPattern` on each face of a cube, in the order left,
+/// front, right, back, up, down.
+#[derive(Clone, Debug)]
+pub struct CubeMap {
+    faces: [Rc<dyn UvPattern>; 6],
+}
+
+impl CubeMap {
+    pub fn new(faces: [Rc<dyn UvPattern>; 6]) -> Self {
+        Self { faces }
+    }
+}
+
+impl Pattern for CubeMap {
+    fn local_color_at(&self, point: Tuple) -> Color {
+        let (face, u, v) = cube_map(point);
+        let index = match face {
+            CubeFace::Left => 0,
+            CubeFace::Front => 1,
+            CubeFace::Right => 2,
+            CubeFace::Back => 3,
+            CubeFace::Up => 4,
+            CubeFace::Down => 5,
+        };
+        self.faces[index].uv_color_at(u, v)
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{
+    color::Color,
+    intersection::{Computations, Intersections},
+    light::{lighting, PointLight},
+    material::Material,
+    matrix::Matrix4x4,
+    ray::Ray,
+    shapes::Object,
+    tuple::Tuple,
+};
+
+pub struct World {
+    pub objects: Vec<Object>,
+    pub lights: Vec<PointLight>,
+    /// How many times a ray may bounce before its color is cut off.
+    pub max_depth: usize,
+}
+
+impl World {
+    pub fn new(objects: Vec<Object>, lights: Vec<PointLight>) -> Self {
+        Self {
+            objects,
+            lights,
+            max_depth: 5,
+        }
+    }
+    /// Two concentric spheres lit from the upper left, as used throughout
+    /// the book's world tests.
+    pub fn default_world() -> Self {
+        let light = PointLight::new(Tuple::point(-10., 10., -10.), Color::new(1., 1., 1.));
+        let outer = Object::sphere(
+            Matrix4x4::_IDENTITY,
+            Material {
+                color: Color::new(0.8, 1., 0.6),
+                diffuse: 0.7,
+                specular: 0.2,
+                ..Material::default()
+            },
+        );
+        let inner = Object::sphere(
+            Matrix4x4::scaling(0.5, 0.5, 0.5).inverse(),
+            Material::default(),
+        );
+        Self::new(vec![outer, inner], vec![light])
+    }
+    /// Every intersection of `ray` with every object, sorted by `t`.
+    pub fn intersect_world(&self, ray: &Ray) -> Intersections<'_> {
+        let mut xs = Intersections::default();
+        for object in &self.objects {
+            xs.merge(object.intersect(*ray));
+        }
+        xs
+    }
+    /// The color at a hit. `remaining` is how many more bounces secondary
+    /// rays may take.
+    pub fn shade_hit(&self, comps: &Computations, remaining: usize) -> Color {
+        let surface = self
+            .lights
+            .iter()
+            .fold(Color::new(0., 0., 0.), |color, light| {
+                color
+                    + lighting(
+                        comps.object.material(),
+                        comps.object,
+                        light,
+                        comps.over_point,
+                        comps.eyev,
+                        comps.normalv,
+                        self.is_shadowed(comps.over_point, light),
+                    )
+            });
+        let reflected = self.reflected_color(comps, remaining);
+        let refracted = self.refracted_color(comps, remaining);
+        let material = comps.object.material();
+        if material.reflective > 0. && material.transparency > 0. {
+            let reflectance = comps.schlick();
+            surface + reflected * reflectance + refracted * (1. - reflectance)
+        } else {
+            surface + reflected + refracted
+        }
+    }
+    pub fn reflected_color(&self, comps: &Computations, remaining: usize) -> Color {
+        let reflective = comps.object.material().reflective;
+        if remaining == 0 || reflective == 0. {
+            return Color::new(0., 0., 0.);
+        }
+        let ray = Ray::new(comps.over_point, comps.reflectv);
+        self.color_at(&ray, remaining - 1) * reflective
+    }
+    /// Whether anything lies between `point` and `light`.
+    pub fn is_shadowed(&self, point: Tuple, light: &PointLight) -> bool {
+        let v = light.position - point;
+        let distance = v.length();
+        let ray = Ray::new(point, v.normalize());
+        self.objects
+            .iter()
+            .any(|object| object.intersects_before(ray, distance))
+    }
+    pub fn refracted_color(&self, comps: &Computations, remaining: usize) -> Color {
+        let transparency = comps.object.material().transparency;
+        if remaining == 0 || transparency == 0. {
+            return Color::new(0., 0., 0.);
+        }
+        // Snell's law, worked through with the cosines of the two angles.
+        let n_ratio = comps.n1 / comps.n2;
+        let cos_i = Tuple::dot(comps.eyev, comps.normalv);
+        let sin2_t = n_ratio * n_ratio * (1. - cos_i * cos_i);
+        if sin2_t > 1. {
+            // Total internal reflection.
+            return Color::new(0., 0., 0.);
+        }
+        let cos_t = (1. - sin2_t).sqrt();
+        let direction = comps.normalv * (n_ratio * cos_i - cos_t) - comps.eyev * n_ratio;
+        let ray = Ray::new(comps.under_point, direction);
+        self.color_at(&ray, remaining - 1) * transparency
+    }
+    pub fn color_at(&self, ray: &Ray, remaining: usize) -> Color {
+        let xs = self.intersect_world(ray);
+        match xs.hit() {
+            Some(hit) => self.shade_hit(&hit.prepare_computations(ray, &xs), remaining),
+            None => Color::new(0., 0., 0.),
+        }
+    }
+}